@@ -1,99 +1,969 @@
-mod pages;
+pub mod action;
+pub mod pages;
 
-use log::trace;
-use pages::{
-    pause::{Pause, PauseOptions},
-    typing::{PromptSettings, TypingStats},
+pub use action::{Action, Selection};
+use log::{error, warn};
+use pages::pause::{Pause, PauseOptions};
+pub use pages::{
+    lessons::{LessonProgress, CURRICULUM},
+    Drill, Heatmap, Lessons, Menu, MenuOptions, Pages, Records, Results, ResultsOptions, Settings,
+    SettingsOptions, Stats,
 };
-pub use pages::{Menu, MenuOptions, Pages, Typing};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use ratatui::{layout::Rect, widgets::ListState};
 
-use std::{cell::RefCell, error, rc::Rc};
+use crate::{
+    config::Config,
+    history::{History, HistoryRecord},
+    stats::{counts_for_pb, personal_bests, PbCategory, Weakness},
+    typing::{Clock, IdleAction, LanguagePrompt, PromptSettings, Typing, TypingStats},
+    ui::prompt::PromptText,
+    Error,
+};
+
+/// Where the words of a new prompt come from.
+#[derive(Debug, Clone, Default)]
+pub enum PromptSource {
+    /// Random words from the selected language.
+    #[default]
+    Language,
+    /// Words containing the given n-grams, or repetitions of them when synthetic.
+    Drill {
+        ngrams: Vec<String>,
+        synthetic: bool,
+    },
+    /// The lesson at this index of the curriculum.
+    Lesson(usize),
+    /// Words missed in an earlier test.
+    Practice(Vec<String>),
+}
+
+// number of recent tests the adaptive prompts are based on, so practice follows recent progress
+const ADAPTIVE_WINDOW: usize = 50;
 
-/// Application result type.
-pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
+// milliseconds between ticks while a test's clock runs, so the timer and live WPM move smoothly
+const ACTIVE_TICK_RATE: u64 = 50;
+
+pub use crate::AppResult;
 
 // TODO we want to store the terminal size, for calculating endline cutoffs for text in the prompt
 /// Application.
 #[derive(Debug)]
-pub struct App<'a> {
+pub struct App {
     pub running: bool,
     pub current_page: Pages,
     pub menu: Menu,
-    pub prompt_settings: Rc<RefCell<PromptSettings>>,
-    pub typing: Typing<'a>,
+    pub settings: Settings,
+    pub heatmap: Heatmap,
+    pub stats: Stats,
+    pub results: Results,
+    pub records: Records,
+    pub drill: Drill,
+    pub lessons: Lessons,
+    pub prompt_source: PromptSource,
+    // seed the next prompt is generated from, a random one is used when `None`
+    pub next_seed: Option<u64>,
+    // seeds of the prompts after the first, derived from the seed given by the user so a
+    // whole session can be repeated
+    seeds: Option<StdRng>,
+    // seed the current prompt was generated from, `None` when adaptive practice also
+    // shaped it, since that depends on the user's history and cannot be reproduced
+    pub prompt_seed: Option<u64>,
+    pub config: Config,
+    pub history: History,
+    // weak letters and bigrams from recent tests, used for adaptive prompts
+    pub weakness: Weakness,
+    // settings for new prompts, changed from the settings page and the settings bar
+    pub prompt_settings: PromptSettings,
+    pub typing: Typing,
+    // styled letters of the prompt, restyled as they are typed
+    pub prompt_text: PromptText,
     pub pause_popup: Pause,
     pub paused: bool,
     pub previous_stats: TypingStats,
+    // ring the terminal bell on the next draw
+    pub bell: bool,
+    // whether the screen is out of date and must be drawn again
+    pub dirty: bool,
+    // error shown in a popup over the current page until a key is pressed
+    pub error: Option<String>,
+    // where the word count, capitalization, punctuation and zen entries of the settings
+    // bar above the prompt were last drawn, used for mouse clicks
+    pub settings_bar: [Rect; 4],
 }
 
-impl<'a> Default for App<'a> {
+impl Default for App {
     fn default() -> Self {
-        // todo settings should be initialized either from config or cli settings, then default
-        let settings = Rc::new(RefCell::new(PromptSettings::new()));
+        Self::with_config(Config::new())
+    }
+}
+
+impl App {
+    /// Constructs a new instance of [`App`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new instance of [`App`] using the settings stored in `config`.
+    pub fn with_config(config: Config) -> Self {
+        let settings = config.prompt.clone();
         Self {
             running: true,
             current_page: Pages::Menu,
             menu: Menu::new(),
-            prompt_settings: settings.clone(),
-            typing: Typing::new(Some(settings.clone())),
+            settings: Settings::new(),
+            heatmap: Heatmap::new(),
+            stats: Stats::new(),
+            results: Results::new(),
+            records: Records::new(),
+            drill: Drill::new(),
+            lessons: Lessons::new(),
+            prompt_source: PromptSource::default(),
+            next_seed: None,
+            seeds: None,
+            prompt_seed: None,
+            config,
+            history: History::new(),
+            weakness: Weakness::new(),
+            // replaced with a generated prompt when a test starts
+            typing: Typing::with_phrase(vec![], settings.clone()),
+            prompt_settings: settings,
+            prompt_text: PromptText::default(),
             pause_popup: Pause::new(),
             paused: false,
             previous_stats: TypingStats {
                 wpm: 0.0,
                 accuracy: 0.0,
                 awpm: 0.0,
+                duration: 0.0,
+                failed: false,
             },
+            bell: false,
+            dirty: true,
+            error: None,
+            settings_bar: Default::default(),
         }
     }
-}
 
-impl<'a> App<'a> {
-    /// Constructs a new instance of [`App`].
-    pub fn new() -> Self {
-        Self::default()
+    /// Generates the first prompt from `seed`, and every later prompt from seeds derived
+    /// from it, so a session can be shared and typed again.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.next_seed = Some(seed);
+        self.seeds = Some(StdRng::seed_from_u64(seed));
+        self
+    }
+
+    /// Times tests with `clock`, a manual clock makes scripted runs reproducible.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.typing.set_clock(clock);
+        self
+    }
+
+    /// Uses `progress` to track completed lessons.
+    pub fn with_lesson_progress(mut self, progress: LessonProgress) -> Self {
+        self.lessons.progress = progress;
+        self
+    }
+
+    /// Uses `history` to record completed tests.
+    pub fn with_history(mut self, history: History) -> Self {
+        self.history = history;
+        self.update_weakness();
+        self
+    }
+
+    // picks up changes made to the history file outside of this session
+    fn reload_history(&mut self) {
+        match self.history.reload_if_changed() {
+            Ok(true) => self.update_weakness(),
+            Ok(false) => {}
+            Err(e) => self.show_error("Failed to reload the history", e),
+        }
+    }
+
+    fn update_weakness(&mut self) {
+        let records = &self.history.records;
+        let recent = &records[records.len().saturating_sub(ADAPTIVE_WINDOW)..];
+        self.weakness = Weakness::from_records(recent);
+    }
+
+    /// Applies `action` to the state of the app.
+    pub fn dispatch(&mut self, action: Action) {
+        // ticks only change the screen while a test runs, or when they end or pause one
+        if let Action::Tick = action {
+            let page = self.current_page;
+            self.tick();
+            self.dirty |= self.current_page != page || self.timer_active();
+            return;
+        }
+        self.dirty = true;
+        match action {
+            Action::Tick => {}
+            Action::AdvanceClock(by) => self.typing.advance_clock(by),
+            // every page is laid out as it is drawn, which marking the app dirty triggers
+            Action::Resize(..) => {}
+            Action::FocusLost => self.focus_lost(),
+            Action::Quit => self.quit(),
+            Action::DismissError => self.error = None,
+            Action::Navigate(page) => {
+                self.paused = false;
+                self.current_page = page;
+            }
+            Action::Select(selection) => self.select(selection),
+            Action::Confirm => self.confirm(),
+            Action::Input(c) => self.input(c),
+            Action::Backspace => match self.current_page {
+                Pages::Typing => self.typing.backspace(),
+                Pages::Drill => {
+                    self.drill.input.pop();
+                }
+                Pages::Settings => {
+                    if let Some(input) = &mut self.settings.input {
+                        input.pop();
+                    }
+                }
+                _ => {}
+            },
+            Action::Paste(text) => match self.current_page {
+                Pages::Typing | Pages::Pause => {
+                    warn!(target: "Input", "refused {} pasted characters during a test", text.len());
+                }
+                _ => text.chars().for_each(|c| self.enter_text(c)),
+            },
+            Action::Pause => self.pause(),
+            Action::Resume => self.unpause(),
+            Action::Retry => self.retry_prompt(),
+            Action::NewPrompt => self.new_prompt(),
+            Action::ToggleSettingsBar(index) => self.toggle_settings_bar(index),
+            Action::AdjustSetting(forward) => self.adjust_setting(forward),
+            Action::CancelInput => {
+                self.settings.input = None;
+                self.settings.message = None;
+            }
+            Action::SaveSettings => self.close_settings(),
+            Action::LeaveSettings => self.leave_settings(),
+            Action::ToggleSynthetic => self.drill.toggle_synthetic(),
+            Action::NextStatsRange => self.stats.next_range(),
+            Action::NextStatsMode => self.stats.next_mode(),
+            Action::NextStatsLanguage => self.stats.next_language(&self.history),
+            Action::NextStatsWc => self.stats.next_wc(&self.history),
+            Action::NextHeatmapLayout => self.heatmap.next_layout(),
+            Action::ToggleHeatmapMetric => self.heatmap.toggle_metric(),
+        }
+    }
+
+    /// Whether a test is running with its clock started.
+    pub fn timer_active(&self) -> bool {
+        self.current_page == Pages::Typing && self.typing.started()
+    }
+
+    /// Milliseconds between ticks, faster while a test runs.
+    pub fn tick_rate(&self) -> u64 {
+        if self.timer_active() {
+            self.config.tick_rate.min(ACTIVE_TICK_RATE)
+        } else {
+            self.config.tick_rate
+        }
+    }
+
+    // the list of the current page and its length, if it has one
+    fn selection(&mut self) -> Option<(&mut ListState, usize)> {
+        match self.current_page {
+            Pages::Menu => Some((&mut self.menu.current_selection, self.menu.options.len())),
+            Pages::Pause => Some((
+                &mut self.pause_popup.current_selection,
+                self.pause_popup.options.len(),
+            )),
+            Pages::Results => Some((
+                &mut self.results.current_selection,
+                self.results.options.len(),
+            )),
+            Pages::Lessons => Some((&mut self.lessons.current_selection, CURRICULUM.len())),
+            Pages::Settings => Some((
+                &mut self.settings.current_selection,
+                self.settings.options.len(),
+            )),
+            _ => None,
+        }
+    }
+
+    fn select(&mut self, selection: Selection) {
+        let Some((state, len)) = self.selection() else {
+            return;
+        };
+        match selection {
+            Selection::None => state.select(None),
+            Selection::Next => state.select_next(),
+            Selection::Previous => state.select_previous(),
+            Selection::First => state.select_first(),
+            Selection::Last => state.select_last(),
+            Selection::Index(i) => state.select(Some(i)),
+        }
+        // ratatui only clamps the selection when the list is drawn, which headless runs never
+        // do, so `select_last` would leave `usize::MAX` behind
+        if let Some(i) = state.selected() {
+            state.select((len > 0).then(|| i.min(len - 1)));
+        }
+    }
+
+    fn confirm(&mut self) {
+        let result = match self.current_page {
+            Pages::Pause => self.select_pause_option(),
+            Pages::Results => self.select_results_option(),
+            Pages::Lessons => self.start_lesson(),
+            Pages::Menu => return self.select_menu_option(),
+            Pages::Drill => return self.start_drill_from_page(),
+            Pages::Settings if self.settings.input.is_some() => return self.commit_setting_input(),
+            Pages::Settings => return self.edit_setting(),
+            _ => return,
+        };
+        if let Err(e) = result {
+            self.show_error("Failed to open the selected option", e);
+        }
     }
 
-    /// Handles the tick event of the terminal.
-    pub fn tick(&self) {}
+    // passes a character to the typing prompt, ringing the bell on mistakes when sounds are on
+    fn input(&mut self, c: char) {
+        if self.current_page != Pages::Typing {
+            self.enter_text(c);
+            return;
+        }
+        let complete = self.typing.input(c);
+        if self.typing.last_was_error() && self.prompt_settings.sounds {
+            self.bell = true;
+        }
+        if complete {
+            self.complete_prompt();
+        }
+    }
+
+    // adds a character to the text being entered on the drill or settings page
+    fn enter_text(&mut self, c: char) {
+        match self.current_page {
+            Pages::Drill if c.is_ascii_graphic() => self.drill.input.push(c),
+            Pages::Settings if c.is_ascii_digit() || c == ',' => {
+                if let Some(input) = &mut self.settings.input {
+                    input.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn tick(&mut self) {
+        if let Pages::Typing = self.current_page {
+            if self.typing.time_up() {
+                self.complete_prompt();
+                return;
+            }
+            self.check_idle();
+        }
+    }
+
+    // pauses or discards a started test after the idle timeout
+    fn check_idle(&mut self) {
+        let (timeout, action) = {
+            let settings = &self.prompt_settings;
+            (settings.idle_timeout, settings.idle_action)
+        };
+        let idle = self.typing.idle_for().unwrap_or_default();
+        if timeout == 0 || idle.as_secs() < timeout as u64 {
+            return;
+        }
+        match action {
+            IdleAction::Pause => {
+                self.typing.pause_idle();
+                self.pause();
+                self.pause_popup.message =
+                    Some(format!("Paused after {}s without typing", timeout));
+            }
+            IdleAction::Discard => {
+                self.typing.reset();
+                self.pause();
+                self.pause_popup.message =
+                    Some(format!("Test discarded after {}s without typing", timeout));
+            }
+        }
+    }
+
+    // pauses a test in progress when the terminal loses focus
+    fn focus_lost(&mut self) {
+        if let Pages::Typing = self.current_page {
+            self.pause();
+            self.pause_popup.message = Some("Paused, the terminal lost focus".to_string());
+        }
+    }
 
-    /// Set running to false to quit the application.
-    pub fn quit(&mut self) {
+    // set running to false to quit the application
+    fn quit(&mut self) {
         self.running = false;
     }
 
-    pub fn pause(&mut self) {
+    fn pause(&mut self) {
         self.paused = true;
+        self.typing.pause();
+        self.pause_popup.message = None;
         self.pause_popup.select_first();
         self.current_page = Pages::Pause;
     }
 
-    pub fn unpause(&mut self) {
+    fn unpause(&mut self) {
         self.paused = false;
+        self.typing.resume();
         self.current_page = Pages::Typing;
     }
 
-    pub fn select_menu_option(&mut self) {
-        match self.menu.current_selection.selected() {
-            Some(x) => {
-                let selected = &self.menu.options[x];
-                match selected {
-                    MenuOptions::Type => self.setup_typing(),
-                    MenuOptions::Quit => self.quit(),
-                }
+    fn select_pause_option(&mut self) -> AppResult<()> {
+        let Some(x) = self.pause_popup.current_selection.selected() else {
+            return Ok(());
+        };
+        match *self.pause_popup.options.get(x).ok_or(Error::NoOption(x))? {
+            PauseOptions::Resume => self.unpause(),
+            PauseOptions::Restart => self.retry_prompt(),
+            PauseOptions::NewPrompt => {
+                self.paused = false;
+                self.new_prompt();
+            }
+            PauseOptions::Settings => self.open_settings(Pages::Pause),
+            PauseOptions::Menu => {
+                self.paused = false;
+                self.current_page = Pages::Menu;
+            }
+            PauseOptions::Quit => self.quit(),
+        }
+        Ok(())
+    }
+
+    fn select_menu_option(&mut self) {
+        // nothing is selected after `h`, so there is nothing to open
+        let Some(selected) = self
+            .menu
+            .current_selection
+            .selected()
+            .and_then(|x| self.menu.options.get(x))
+        else {
+            return;
+        };
+        match selected {
+            MenuOptions::Type => {
+                self.prompt_source = PromptSource::Language;
+                self.setup_typing()
+            }
+            MenuOptions::Lessons => self.open_lessons(),
+            MenuOptions::Drill => self.open_drill(),
+            MenuOptions::History => self.open_history(),
+            MenuOptions::Records => self.open_records(),
+            MenuOptions::KeyStats => self.open_heatmap(),
+            MenuOptions::Settings => self.open_settings(Pages::Menu),
+            MenuOptions::Quit => self.quit(),
+        }
+    }
+
+    // logs `error` and shows it in a popup, so it is not missed
+    fn show_error(&mut self, context: &str, error: impl std::fmt::Display) {
+        error!(target: "app", "{}: {}", context, error);
+        self.error = Some(format!("{}: {}", context, error));
+    }
+
+    fn open_lessons(&mut self) {
+        self.lessons.message = None;
+        self.current_page = Pages::Lessons;
+    }
+
+    // starts the selected lesson if it is unlocked
+    fn start_lesson(&mut self) -> AppResult<()> {
+        let Some(index) = self.lessons.current_selection.selected() else {
+            return Ok(());
+        };
+        let lesson = CURRICULUM.get(index).ok_or(Error::NoOption(index))?;
+        if !self.lessons.progress.is_unlocked(index) {
+            // the first lesson is always unlocked, so a locked one has a previous lesson
+            let previous = &CURRICULUM[index - 1];
+            self.lessons.message =
+                Some(format!("Pass {} to unlock {}", previous.name, lesson.name));
+            return Ok(());
+        }
+        self.prompt_source = PromptSource::Lesson(index);
+        self.setup_typing();
+        Ok(())
+    }
+
+    // unlocks the next lesson when the pass criteria are met
+    fn grade_lesson(&mut self, index: usize, stats: &TypingStats) {
+        let lesson = &CURRICULUM[index];
+        if stats.failed || !lesson.passed(stats.wpm, stats.accuracy) {
+            self.lessons.message = Some(format!(
+                "{}: {:.0} wpm at {:.0}%, {:.0} wpm at {:.0}% needed to pass",
+                lesson.name, stats.wpm, stats.accuracy, lesson.min_wpm, lesson.min_accuracy
+            ));
+            return;
+        }
+        self.lessons.progress.complete(index);
+        self.lessons.message = Some(match CURRICULUM.get(index + 1) {
+            Some(next) => format!("Passed {}! {} unlocked", lesson.name, next.name),
+            None => format!("Passed {}! Curriculum complete", lesson.name),
+        });
+        if let Err(e) = self.lessons.progress.save() {
+            self.show_error("Failed to save lesson progress", e);
+        }
+    }
+
+    fn open_drill(&mut self) {
+        self.drill.message = None;
+        self.current_page = Pages::Drill;
+    }
+
+    // starts typing drills for the n-grams entered on the drill page
+    fn start_drill_from_page(&mut self) {
+        match self.drill.ngrams() {
+            Ok(ngrams) => self.start_drill(ngrams, self.drill.synthetic),
+            Err(e) => self.drill.message = Some(e),
+        }
+    }
+
+    /// Starts typing drills for `ngrams`.
+    pub fn start_drill(&mut self, ngrams: Vec<String>, synthetic: bool) {
+        self.prompt_source = PromptSource::Drill { ngrams, synthetic };
+        self.setup_typing();
+    }
+
+    fn open_history(&mut self) {
+        self.reload_history();
+        self.current_page = Pages::Stats;
+    }
+
+    fn open_records(&mut self) {
+        self.reload_history();
+        self.records.refresh(&self.history);
+        self.current_page = Pages::Records;
+    }
+
+    fn open_heatmap(&mut self) {
+        self.reload_history();
+        self.heatmap.refresh(&self.history);
+        self.current_page = Pages::Heatmap;
+    }
+
+    fn open_settings(&mut self, return_to: Pages) {
+        self.settings.message = None;
+        self.settings.return_to = return_to;
+        self.current_page = Pages::Settings;
+    }
+
+    // returns to the page the settings were opened from
+    fn leave_settings(&mut self) {
+        self.current_page = self.settings.return_to;
+    }
+
+    // changes the selected setting to its next or previous value
+    fn adjust_setting(&mut self, forward: bool) {
+        if let Some(option) = self.settings.selected() {
+            option.adjust(&mut self.prompt_settings, forward);
+            self.settings.message = None;
+        }
+    }
+
+    // starts text entry for editable settings, other settings move to their next value
+    fn edit_setting(&mut self) {
+        match self.settings.selected() {
+            Some(option) if option.is_editable() => {
+                self.settings.input = Some(option.edit_value(&self.prompt_settings));
+                self.settings.message = None;
+            }
+            Some(_) => self.adjust_setting(true),
+            None => {}
+        }
+    }
+
+    // applies the entered text to the selected setting, keeping the entry open if it is invalid
+    fn commit_setting_input(&mut self) {
+        let (Some(option), Some(input)) = (self.settings.selected(), &self.settings.input) else {
+            return;
+        };
+        match option.apply_input(&mut self.prompt_settings, input) {
+            Ok(()) => {
+                self.settings.input = None;
+                self.settings.message = None;
+            }
+            Err(e) => self.settings.message = Some(e),
+        }
+    }
+
+    // validates and saves the settings to the config file, then returns to the page
+    // the settings were opened from
+    fn close_settings(&mut self) {
+        self.prompt_settings.validate();
+        self.config.prompt = self.prompt_settings.clone();
+        match self.config.save() {
+            Ok(()) => self.leave_settings(),
+            Err(e) => {
+                error!(target: "settings", "failed to save config: {}", e);
+                self.settings.message = Some(format!("Failed to save config: {}", e));
             }
-            None => todo!(),
         }
     }
 
     fn setup_typing(&mut self) {
+        let language = match LanguagePrompt::for_settings(&self.prompt_settings) {
+            Ok(language) => language,
+            Err(e) => return self.show_error("Failed to start a test", e),
+        };
+        // a seed given by the user should give the same prompt as for anyone else, so the
+        // prompt is not adapted to this user's weaknesses
+        let next_seed = self.next_seed.take();
+        let next_seed = next_seed.or_else(|| self.seeds.as_mut().map(|seeds| seeds.gen()));
+        let (seed, weakness) = match next_seed {
+            Some(seed) => (seed, None),
+            None => (rand::random(), Some(&self.weakness)),
+        };
+        let phrase = {
+            let settings = &self.prompt_settings;
+            let adapted = settings.adaptive && weakness.is_some_and(|w| !w.is_empty());
+            self.prompt_seed = Some(seed);
+            match &self.prompt_source {
+                PromptSource::Language => {
+                    if adapted {
+                        self.prompt_seed = None;
+                    }
+                    language.generate(settings, weakness, seed)
+                }
+                PromptSource::Drill { ngrams, synthetic } => {
+                    language.generate_drill(ngrams, *synthetic, settings.word_count(), seed)
+                }
+                PromptSource::Practice(words) => {
+                    LanguagePrompt::generate_practice(words, settings.word_count(), seed)
+                }
+                PromptSource::Lesson(index) => {
+                    let lesson = &CURRICULUM[*index];
+                    language.generate_lesson(
+                        &lesson.charset(),
+                        lesson.keys,
+                        settings.word_count(),
+                        seed,
+                    )
+                }
+            }
+        };
+        if phrase.is_empty() {
+            let e = Error::Invalid("there are no words to type".to_string());
+            return self.show_error("Failed to start a test", e);
+        }
+        let clock = self.typing.clock();
+        self.typing = Typing::with_phrase(phrase, self.prompt_settings.clone());
+        self.typing.set_clock(clock);
+        self.current_page = Pages::Typing;
+    }
+
+    // records the finished test in the history, then shows its results
+    fn complete_prompt(&mut self) {
+        let stats = self.typing.calculate_statistics();
+        let mut record = HistoryRecord::new(
+            &self.typing.settings,
+            &stats,
+            self.typing.keystrokes().to_vec(),
+        );
+        record.seed = self.prompt_seed;
+        record.timestamp = self.typing.clock().timestamp();
+        match self.prompt_source {
+            PromptSource::Drill { ref ngrams, .. } => record.drill = Some(ngrams.clone()),
+            PromptSource::Practice(ref words) => record.practice = Some(words.clone()),
+            PromptSource::Lesson(index) => {
+                record.lesson = Some(CURRICULUM[index].id.to_string());
+                self.grade_lesson(index, &stats);
+            }
+            PromptSource::Language => {}
+        }
+
+        self.reload_history();
+        let previous_best = personal_bests(&self.history.records)
+            .get(&PbCategory::of(&record))
+            .map(|best| best.wpm);
+        self.results.new_pb =
+            counts_for_pb(&record) && previous_best.is_none_or(|best| record.wpm > best);
+        self.results.previous_best = previous_best;
+        self.results.flagged = record.flagged;
+
+        if let Err(e) = self.history.push(record) {
+            self.show_error("Failed to save the test to the history", e);
+        }
+        self.update_weakness();
+
+        // lesson results are shown on the lesson list, which may have unlocked a new lesson
+        if let PromptSource::Lesson(_) = self.prompt_source {
+            self.previous_stats = stats;
+            self.current_page = Pages::Lessons;
+            return;
+        }
+        self.previous_stats = stats;
+        self.results.show(self.typing.missed_words());
+        self.current_page = Pages::Results;
+    }
+
+    fn select_results_option(&mut self) -> AppResult<()> {
+        let Some(x) = self.results.current_selection.selected() else {
+            return Ok(());
+        };
+        match *self.results.options.get(x).ok_or(Error::NoOption(x))? {
+            ResultsOptions::NextTest => {
+                // practice is a one off, the next test goes back to regular prompts
+                if let PromptSource::Practice(_) = self.prompt_source {
+                    self.prompt_source = PromptSource::Language;
+                }
+                self.setup_typing()
+            }
+            ResultsOptions::Retry => self.retry_prompt(),
+            ResultsOptions::PracticeMissed => {
+                self.prompt_source = PromptSource::Practice(self.results.missed_words.clone());
+                self.setup_typing()
+            }
+            ResultsOptions::Menu => self.current_page = Pages::Menu,
+        }
+        Ok(())
+    }
+
+    // starts the current prompt over from the beginning
+    fn retry_prompt(&mut self) {
+        self.typing.reset();
+        self.paused = false;
         self.current_page = Pages::Typing;
-        self.typing = Typing::new(Some(self.typing.settings.clone()));
     }
 
-    pub fn new_prompt(&mut self) {
+    fn new_prompt(&mut self) {
         self.previous_stats = self.typing.calculate_statistics();
         self.setup_typing();
     }
 
-    pub fn resize(&mut self, height: u16, width: u16) {}
+    // changes the setting of the settings bar entry at `index`, a prompt shaped by the
+    // setting is generated again so the test matches the settings it is recorded with
+    fn toggle_settings_bar(&mut self, index: usize) {
+        let settings = &mut self.prompt_settings;
+        match index {
+            0 => settings.next_wc(),
+            1 => settings.toggle_capitalization(),
+            2 => settings.toggle_punctuation(),
+            // zen only changes how the test is drawn
+            3 => {
+                settings.toggle_zen();
+                return;
+            }
+            _ => return,
+        }
+        if self.current_page == Pages::Typing {
+            self.regenerate_prompt();
+        }
+    }
+
+    // generates the prompt again with the current settings, from the same seed so a shared
+    // prompt stays reproducible
+    fn regenerate_prompt(&mut self) {
+        if self.next_seed.is_none() {
+            self.next_seed = self.prompt_seed;
+        }
+        self.setup_typing();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::{parse_script, EventHandler},
+        export::{export, ExportFilter, ExportFormat},
+        handler::play,
+        typing::Mode,
+    };
+
+    fn assert_send<T: Send>() {}
+
+    // plays `script` back as a headless run does, without ever drawing the app
+    fn play_script(app: &mut App, script: &str) {
+        let mut events = EventHandler::from_events(parse_script(script).unwrap());
+        futures::executor::block_on(play(&mut events, app)).unwrap();
+    }
+
+    fn typed_prompt(app: &App) -> String {
+        let text: String = app.typing.letters().iter().map(|l| l.value).collect();
+        text.replace('•', " ")
+    }
+
+    #[test]
+    fn test_app_is_send() {
+        assert_send::<App>();
+    }
+
+    #[test]
+    fn test_actions_run_a_test_without_a_terminal() {
+        let mut app = App::new().with_seed(7);
+        app.prompt_settings.wc = 3;
+        app.dispatch(Action::Select(Selection::First));
+        app.dispatch(Action::Confirm);
+        assert_eq!(app.current_page, Pages::Typing);
+
+        app.dispatch(Action::ToggleSettingsBar(2));
+        assert!(app.prompt_settings.punctuation);
+        assert!(app.typing.settings.punctuation);
+
+        let phrase: Vec<char> = app.typing.letters().iter().map(|l| l.value).collect();
+        for c in phrase {
+            app.dispatch(Action::Input(c));
+        }
+        assert_eq!(app.current_page, Pages::Results);
+        assert_eq!(app.history.records.len(), 1);
+        assert_eq!(app.history.records[0].seed, Some(7));
+        assert!(app.history.records[0].punctuation);
+    }
+
+    #[test]
+    fn test_settings_changed_during_a_test_apply_to_the_next_prompt() {
+        let mut app = App::new().with_seed(7);
+        app.prompt_settings.wc = 3;
+        app.dispatch(Action::Confirm);
+        let phrase: Vec<char> = app.typing.letters().iter().map(|l| l.value).collect();
+        app.dispatch(Action::Input(phrase[0]));
+
+        // pause, then switch to time mode with punctuation from the settings page
+        app.dispatch(Action::Pause);
+        app.dispatch(Action::Select(Selection::Index(3)));
+        app.dispatch(Action::Confirm);
+        assert_eq!(app.current_page, Pages::Settings);
+        app.dispatch(Action::Select(Selection::Index(2)));
+        app.dispatch(Action::AdjustSetting(true));
+        app.dispatch(Action::Select(Selection::Index(7)));
+        app.dispatch(Action::AdjustSetting(true));
+        app.dispatch(Action::LeaveSettings);
+        app.dispatch(Action::Resume);
+        assert_eq!(app.prompt_settings.mode, Mode::Time);
+        assert!(app.prompt_settings.punctuation);
+
+        for &c in &phrase[1..] {
+            app.dispatch(Action::Input(c));
+        }
+        assert_eq!(app.current_page, Pages::Results);
+        let record = &app.history.records[0];
+        assert_eq!(record.mode, Mode::Words);
+        assert_eq!(record.wc, 3);
+        assert!(!record.punctuation);
+    }
+
+    #[test]
+    fn test_ticks_only_redraw_and_speed_up_while_a_test_runs() {
+        let mut app = App::new().with_clock(Clock::manual());
+        app.dirty = false;
+        app.dispatch(Action::Tick);
+        assert!(!app.dirty);
+        assert_eq!(app.tick_rate(), app.config.tick_rate);
+
+        app.dispatch(Action::Confirm);
+        assert!(app.dirty);
+        app.dirty = false;
+        app.dispatch(Action::Tick);
+        assert!(!app.dirty);
+
+        let first = app.typing.letters()[0].value;
+        app.dispatch(Action::Input(first));
+        app.dirty = false;
+        app.dispatch(Action::Tick);
+        assert!(app.dirty);
+        assert_eq!(app.tick_rate(), ACTIVE_TICK_RATE);
+    }
+
+    #[test]
+    fn test_headless_pause_selection_stays_in_the_list() {
+        let mut app = App::new().with_seed(3).with_clock(Clock::manual());
+        play_script(&mut app, "<enter><esc>G<enter>");
+        assert!(!app.running);
+
+        let mut app = App::new().with_seed(3).with_clock(Clock::manual());
+        play_script(&mut app, "<enter><esc>jjjjjjjjjj<enter>");
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_headless_results_selection_stays_in_the_list() {
+        for moves in ["G", "jjjjjjjjjj"] {
+            let mut app = App::new().with_seed(3).with_clock(Clock::manual());
+            app.prompt_settings.wc = 2;
+            play_script(&mut app, "<enter>");
+            let text = typed_prompt(&app);
+            play_script(&mut app, &text);
+            assert_eq!(app.current_page, Pages::Results);
+            play_script(&mut app, &format!("{}<enter>", moves));
+            assert_eq!(app.current_page, Pages::Menu);
+        }
+    }
+
+    #[test]
+    fn test_headless_lesson_selection_stays_in_the_list() {
+        for moves in ["G", "jjjjjjjjjjjjjjjjjjjj"] {
+            let mut app = App::new().with_clock(Clock::manual());
+            play_script(&mut app, "j<enter>");
+            assert_eq!(app.current_page, Pages::Lessons);
+            play_script(&mut app, &format!("{}<enter>", moves));
+            assert_eq!(app.current_page, Pages::Lessons);
+            let last = CURRICULUM.last().unwrap().name;
+            assert!(app.lessons.message.as_deref().unwrap().ends_with(last));
+        }
+    }
+
+    #[test]
+    fn test_headless_runs_with_a_seed_repeat_exactly() {
+        let run = |script: &str| {
+            let mut app = App::new().with_seed(5).with_clock(Clock::manual());
+            app.prompt_settings.wc = 3;
+            play_script(&mut app, script);
+            app
+        };
+        // a script completing two tests, typing each prompt as it comes up
+        let mut script = "<enter>".to_string();
+        script += &typed_prompt(&run(&script));
+        script += "<enter>";
+        let app = run(&script);
+        assert_eq!(app.current_page, Pages::Typing);
+        script += &typed_prompt(&app);
+
+        let output = |app: &App| {
+            let mut out = vec![];
+            let filter = ExportFilter::default();
+            export(
+                &app.history.records,
+                &mut out,
+                ExportFormat::Json,
+                &filter,
+                true,
+            )
+            .unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let (first, second) = (run(&script), run(&script));
+        assert_eq!(first.history.records.len(), 2);
+        assert!(first.history.records.iter().all(|r| r.seed.is_some()));
+        assert_eq!(output(&first), output(&second));
+    }
+
+    #[test]
+    fn test_errors_are_shown_instead_of_panicking() {
+        let mut app = App::new();
+        app.dispatch(Action::Select(Selection::None));
+        app.dispatch(Action::Confirm);
+        assert_eq!(app.current_page, Pages::Menu);
+        assert!(app.error.is_none());
+
+        app.show_error(
+            "Failed to start a test",
+            crate::Error::Language("klingon".to_string()),
+        );
+        assert_eq!(
+            app.error.as_deref(),
+            Some("Failed to start a test: no word bank named 'klingon' is available")
+        );
+
+        app.dispatch(Action::DismissError);
+        assert!(app.error.is_none());
+
+        app.dispatch(Action::Navigate(Pages::Lessons));
+        app.lessons.current_selection.select(Some(usize::MAX));
+        app.dispatch(Action::Confirm);
+        assert_eq!(app.current_page, Pages::Lessons);
+        assert!(app.error.is_some());
+    }
 }
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use super::Pages;
+
+/// Moves the selection of a list.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    None,
+    Next,
+    Previous,
+    First,
+    Last,
+    Index(usize),
+}
+
+/// A change to the state of the [`App`](super::App). The handlers turn terminal events into
+/// actions, which are applied with [`App::dispatch`](super::App::dispatch), so the app can be
+/// driven without a terminal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Time passed, ends timed tests and checks for an idle user.
+    Tick,
+    /// Moves a manual clock forward, see [`Clock`](crate::typing::Clock).
+    AdvanceClock(Duration),
+    /// The terminal was resized to this many columns and rows, the screen is drawn again.
+    Resize(u16, u16),
+    /// The terminal lost focus, a test in progress is paused.
+    FocusLost,
+    Quit,
+    /// Closes the error popup.
+    DismissError,
+    /// Shows a page as it was left, leaving a paused test.
+    Navigate(Pages),
+    /// Moves the selection of the list on the current page.
+    Select(Selection),
+    /// Activates the selected item of the current page, or applies the setting being edited.
+    Confirm,
+    /// A typed character, for the prompt or the text being entered on the current page.
+    /// Spaces in the prompt are typed as `•`.
+    Input(char),
+    /// Removes the last typed character.
+    Backspace,
+    /// Text pasted into the terminal, refused during a test.
+    Paste(String),
+    Pause,
+    Resume,
+    /// Starts the current prompt over.
+    Retry,
+    /// Skips to a new prompt.
+    NewPrompt,
+    /// Changes the entry of the settings bar above the prompt at this index.
+    ToggleSettingsBar(usize),
+    /// Moves the selected setting to its next value, or previous when `false`.
+    AdjustSetting(bool),
+    /// Stops editing a setting without applying the entered text.
+    CancelInput,
+    /// Saves the settings and returns to the page they were opened from.
+    SaveSettings,
+    /// Returns from the settings without saving, changes still apply to this session.
+    LeaveSettings,
+    ToggleSynthetic,
+    NextStatsRange,
+    NextStatsMode,
+    NextStatsLanguage,
+    NextStatsWc,
+    NextHeatmapLayout,
+    ToggleHeatmapMetric,
+}
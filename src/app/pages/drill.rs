@@ -0,0 +1,54 @@
+// Common n-grams suggested on the drill page
+pub const SUGGESTED_NGRAMS: [&str; 8] = ["th", "he", "in", "er", "an", "ing", "ion", "tion"];
+
+/// Form for choosing which n-grams to drill.
+#[derive(Debug)]
+pub struct Drill {
+    // comma separated n-grams as entered by the user
+    pub input: String,
+    // repeat the n-grams themselves instead of using real words
+    pub synthetic: bool,
+    // validation error for the entered n-grams
+    pub message: Option<String>,
+}
+
+impl Default for Drill {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drill {
+    pub fn new() -> Self {
+        Self {
+            input: SUGGESTED_NGRAMS[..2].join(","),
+            synthetic: false,
+            message: None,
+        }
+    }
+
+    pub fn toggle_synthetic(&mut self) {
+        self.synthetic = !self.synthetic;
+    }
+
+    /// The entered n-grams, or an error when there are none.
+    pub fn ngrams(&self) -> Result<Vec<String>, String> {
+        parse_ngrams(&self.input)
+    }
+}
+
+/// Splits a comma separated list of n-grams, ignoring empty entries.
+pub fn parse_ngrams(input: &str) -> Result<Vec<String>, String> {
+    let ngrams: Vec<String> = input
+        .split(',')
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .collect();
+    if ngrams.is_empty() {
+        return Err("enter at least one n-gram, e.g. th,ing".to_string());
+    }
+    if let Some(n) = ngrams.iter().find(|n| n.contains(char::is_whitespace)) {
+        return Err(format!("'{}' cannot contain spaces", n));
+    }
+    Ok(ngrams)
+}
@@ -0,0 +1,171 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    history::History,
+    stats::{key_stats, KeyStats},
+};
+
+/// Physical keyboard layouts the heatmap can be drawn as.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+    Dvorak,
+    Colemak,
+}
+
+impl KeyboardLayout {
+    pub const ALL: [KeyboardLayout; 3] = [
+        KeyboardLayout::Qwerty,
+        KeyboardLayout::Dvorak,
+        KeyboardLayout::Colemak,
+    ];
+
+    /// Unshifted character of every key, row by row.
+    pub fn rows(&self) -> [&'static str; 4] {
+        match self {
+            KeyboardLayout::Qwerty => [
+                "`1234567890-=",
+                "qwertyuiop[]\\",
+                "asdfghjkl;'",
+                "zxcvbnm,./",
+            ],
+            KeyboardLayout::Dvorak => [
+                "`1234567890[]",
+                "',.pyfgcrl/=\\",
+                "aoeuidhtns-",
+                ";qjkxbmwvz",
+            ],
+            KeyboardLayout::Colemak => [
+                "`1234567890-=",
+                "qwfpgjluy;[]\\",
+                "arstdhneio'",
+                "zxcvbkm,./",
+            ],
+        }
+    }
+}
+
+impl fmt::Display for KeyboardLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyboardLayout::Qwerty => write!(f, "QWERTY"),
+            KeyboardLayout::Dvorak => write!(f, "Dvorak"),
+            KeyboardLayout::Colemak => write!(f, "Colemak"),
+        }
+    }
+}
+
+/// What the key colors represent.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum HeatmapMetric {
+    #[default]
+    ErrorRate,
+    Latency,
+}
+
+impl fmt::Display for HeatmapMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeatmapMetric::ErrorRate => write!(f, "error rate"),
+            HeatmapMetric::Latency => write!(f, "slowness"),
+        }
+    }
+}
+
+// character produced by holding shift on a US keyboard, used to fold both onto one key
+fn shifted(c: char) -> Option<char> {
+    if c.is_ascii_lowercase() {
+        return Some(c.to_ascii_uppercase());
+    }
+    let (plain, shift) = ("`1234567890-=[]\\;',./", "~!@#$%^&*()_+{}|:\"<>?");
+    plain
+        .chars()
+        .position(|p| p == c)
+        .and_then(|i| shift.chars().nth(i))
+}
+
+#[derive(Debug, Default)]
+pub struct Heatmap {
+    pub layout: KeyboardLayout,
+    pub metric: HeatmapMetric,
+    // stats per physical key, keyed by its unshifted character
+    pub keys: BTreeMap<char, KeyStats>,
+}
+
+impl Heatmap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recomputes the per key stats from every test in the history.
+    pub fn refresh(&mut self, history: &History) {
+        let stats = key_stats(&history.records);
+        self.keys = BTreeMap::new();
+        for row in KeyboardLayout::Qwerty.rows() {
+            for key in row.chars() {
+                let mut combined = stats.get(&key).cloned().unwrap_or_default();
+                if let Some(other) = shifted(key).and_then(|s| stats.get(&s)) {
+                    combined.merge(other);
+                }
+                self.keys.insert(key, combined);
+            }
+        }
+        // the space bar is not part of the rows
+        self.keys
+            .insert(' ', stats.get(&' ').cloned().unwrap_or_default());
+    }
+
+    pub fn next_layout(&mut self) {
+        let i = KeyboardLayout::ALL
+            .iter()
+            .position(|l| *l == self.layout)
+            .unwrap_or(0);
+        self.layout = KeyboardLayout::ALL[(i + 1) % KeyboardLayout::ALL.len()];
+    }
+
+    pub fn toggle_metric(&mut self) {
+        self.metric = match self.metric {
+            HeatmapMetric::ErrorRate => HeatmapMetric::Latency,
+            HeatmapMetric::Latency => HeatmapMetric::ErrorRate,
+        };
+    }
+
+    /// How bad a key is on the current metric, from 0 (best) to 1 (worst).
+    /// `None` when the key has never been typed.
+    pub fn heat(&self, key: char) -> Option<f32> {
+        let stats = self.keys.get(&key).filter(|s| s.presses() > 0)?;
+        match self.metric {
+            // a 20% error rate is already as bad as it gets
+            HeatmapMetric::ErrorRate => Some((stats.error_rate() * 5.0).min(1.0)),
+            HeatmapMetric::Latency => {
+                let slowest = self
+                    .keys
+                    .values()
+                    .filter_map(|s| s.average_latency())
+                    .fold(0.0, f32::max);
+                let fastest = self
+                    .keys
+                    .values()
+                    .filter_map(|s| s.average_latency())
+                    .fold(f32::MAX, f32::min);
+                let latency = stats.average_latency()?;
+                if slowest <= fastest {
+                    return Some(0.0);
+                }
+                Some((latency - fastest) / (slowest - fastest))
+            }
+        }
+    }
+
+    /// Keys sorted from worst to best on the current metric.
+    pub fn worst_keys(&self, n: usize) -> Vec<(char, &KeyStats)> {
+        let mut keys: Vec<(char, &KeyStats, f32)> = self
+            .keys
+            .iter()
+            .filter_map(|(c, s)| self.heat(*c).map(|h| (*c, s, h)))
+            .collect();
+        keys.sort_by(|a, b| b.2.total_cmp(&a.2));
+        keys.into_iter().take(n).map(|(c, s, _)| (c, s)).collect()
+    }
+}
@@ -0,0 +1,175 @@
+use std::{fs, path::PathBuf};
+
+use ratatui::widgets::ListState;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppResult;
+
+const PROGRESS_FILE: &str = "lessons.json";
+
+/// A step of the touch typing curriculum.
+#[derive(Debug)]
+pub struct Lesson {
+    pub id: &'static str,
+    pub name: &'static str,
+    // keys introduced by this lesson, practiced more often than the rest
+    pub keys: &'static str,
+    pub min_wpm: f32,
+    pub min_accuracy: f32,
+}
+
+/// Lessons in the order they unlock. Each lesson uses its own keys and those of every lesson before it.
+pub const CURRICULUM: [Lesson; 5] = [
+    Lesson {
+        id: "home-row",
+        name: "Home row",
+        keys: "asdfghjkl",
+        min_wpm: 15.0,
+        min_accuracy: 90.0,
+    },
+    Lesson {
+        id: "top-row",
+        name: "Top row",
+        keys: "qwertyuiop",
+        min_wpm: 20.0,
+        min_accuracy: 92.0,
+    },
+    Lesson {
+        id: "bottom-row",
+        name: "Bottom row",
+        keys: "zxcvbnm",
+        min_wpm: 25.0,
+        min_accuracy: 92.0,
+    },
+    Lesson {
+        id: "numbers",
+        name: "Numbers",
+        keys: "1234567890",
+        min_wpm: 20.0,
+        min_accuracy: 90.0,
+    },
+    Lesson {
+        id: "symbols",
+        name: "Symbols",
+        keys: ",.;:'\"!?-()",
+        min_wpm: 20.0,
+        min_accuracy: 90.0,
+    },
+];
+
+impl Lesson {
+    /// Every character allowed in this lesson's prompts.
+    pub fn charset(&self) -> String {
+        CURRICULUM
+            .iter()
+            .take_while(|l| l.id != self.id)
+            .chain(std::iter::once(self))
+            .map(|l| l.keys)
+            .collect()
+    }
+
+    pub fn passed(&self, wpm: f32, accuracy: f32) -> bool {
+        wpm >= self.min_wpm && accuracy >= self.min_accuracy
+    }
+}
+
+/// Completed lessons, stored as json in the user's data directory.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct LessonProgress {
+    pub completed: Vec<String>,
+    // where progress is saved, `None` keeps it in memory only
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl LessonProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default location of the progress file, `<data dir>/type-fast/lessons.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("type-fast").join(PROGRESS_FILE))
+    }
+
+    /// Loads progress from `path`, a missing file means no lessons are completed.
+    pub fn load(path: PathBuf) -> AppResult<Self> {
+        let mut progress: Self = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            Self::new()
+        };
+        progress.path = Some(path);
+        Ok(progress)
+    }
+
+    pub fn save(&self) -> AppResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_completed(&self, index: usize) -> bool {
+        self.completed.iter().any(|id| id == CURRICULUM[index].id)
+    }
+
+    /// The first lesson is always unlocked, every other one once the lesson before it is passed.
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index == 0 || self.is_completed(index - 1)
+    }
+
+    pub fn complete(&mut self, index: usize) {
+        if !self.is_completed(index) {
+            self.completed.push(CURRICULUM[index].id.to_string());
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Lessons {
+    pub current_selection: ListState,
+    pub progress: LessonProgress,
+    // outcome of the last lesson attempt, or why a lesson cannot be started
+    pub message: Option<String>,
+}
+
+impl Default for Lessons {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lessons {
+    pub fn new() -> Self {
+        // initialize state as the first item in the list
+        let mut state = ListState::default();
+        state.select_first();
+        Self {
+            current_selection: state,
+            progress: LessonProgress::new(),
+            message: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lessons_unlock_in_order() {
+        let mut progress = LessonProgress::new();
+        assert!(progress.is_unlocked(0));
+        assert!(!progress.is_unlocked(1));
+
+        progress.complete(0);
+        assert!(progress.is_unlocked(1));
+        assert!(!progress.is_unlocked(2));
+        assert_eq!(CURRICULUM[1].charset(), "asdfghjklqwertyuiop");
+    }
+}
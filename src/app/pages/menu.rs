@@ -1,11 +1,19 @@
 use std::fmt;
 
-use ratatui::widgets::ListState;
+use ratatui::{layout::Rect, widgets::ListState};
+
+use super::list_item_at;
 
 // Available menu options when first running the application
 #[derive(Debug)]
 pub enum MenuOptions {
     Type,
+    Lessons,
+    Drill,
+    History,
+    Records,
+    KeyStats,
+    Settings,
     Quit,
 }
 
@@ -14,6 +22,12 @@ impl fmt::Display for MenuOptions {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             MenuOptions::Type => write!(f, "Type"),
+            MenuOptions::Lessons => write!(f, "Lessons"),
+            MenuOptions::Drill => write!(f, "Drill"),
+            MenuOptions::History => write!(f, "History"),
+            MenuOptions::Records => write!(f, "Records"),
+            MenuOptions::KeyStats => write!(f, "Key Stats"),
+            MenuOptions::Settings => write!(f, "Settings"),
             MenuOptions::Quit => write!(f, "Quit"),
         }
     }
@@ -23,6 +37,14 @@ impl fmt::Display for MenuOptions {
 pub struct Menu {
     pub options: Vec<MenuOptions>,
     pub current_selection: ListState,
+    // where the list was last drawn, used to find the item under a mouse click
+    pub area: Rect,
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Menu {
@@ -31,8 +53,18 @@ impl Menu {
         let mut state = ListState::default();
         state.select_first();
         Self {
-            options: vec![MenuOptions::Type, MenuOptions::Quit],
+            options: vec![
+                MenuOptions::Type,
+                MenuOptions::Lessons,
+                MenuOptions::Drill,
+                MenuOptions::History,
+                MenuOptions::Records,
+                MenuOptions::KeyStats,
+                MenuOptions::Settings,
+                MenuOptions::Quit,
+            ],
             current_selection: state,
+            area: Rect::default(),
         }
     }
 
@@ -40,6 +72,17 @@ impl Menu {
         self.current_selection.select(None);
     }
 
+    /// Index of the item drawn at `row`, if any.
+    pub fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        list_item_at(
+            self.area,
+            &self.current_selection,
+            self.options.len(),
+            column,
+            row,
+        )
+    }
+
     pub fn select_next(&mut self) {
         self.current_selection.select_next();
     }
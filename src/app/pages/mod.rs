@@ -1,14 +1,70 @@
 pub mod menu;
 pub use menu::{Menu, MenuOptions};
-pub mod typing;
-pub use typing::{PromptSettings, Typing};
+pub mod drill;
+pub use drill::Drill;
+pub mod heatmap;
+pub mod lessons;
+pub use heatmap::Heatmap;
+pub use lessons::Lessons;
 pub mod pause;
+pub mod records;
+pub mod results;
+pub mod settings;
+pub mod stats;
+pub use records::Records;
+pub use results::{Results, ResultsOptions};
+pub use settings::{Settings, SettingsOptions};
+pub use stats::Stats;
+
+use ratatui::{
+    layout::{Position, Rect},
+    widgets::ListState,
+};
+
+/// Index of the item of a list with `len` items drawn in `area` that is at `column`, `row`,
+/// taking the list's scroll offset into account.
+pub fn list_item_at(
+    area: Rect,
+    state: &ListState,
+    len: usize,
+    column: u16,
+    row: u16,
+) -> Option<usize> {
+    if !area.contains(Position::new(column, row)) {
+        return None;
+    }
+    let index = state.offset() + (row - area.y) as usize;
+    (index < len).then_some(index)
+}
 
 // All possible pages the user could be shown
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Pages {
     Menu,
     Typing,
     Stats,
     Pause,
+    Settings,
+    Heatmap,
+    Drill,
+    Lessons,
+    Results,
+    Records,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_item_at_accounts_for_scroll_offset() {
+        let area = Rect::new(10, 5, 20, 3);
+        let mut state = ListState::default().with_offset(2);
+        assert_eq!(list_item_at(area, &state, 10, 12, 6), Some(3));
+        assert_eq!(list_item_at(area, &state, 10, 12, 4), None);
+        assert_eq!(list_item_at(area, &state, 3, 12, 6), None);
+
+        *state.offset_mut() = 0;
+        assert_eq!(list_item_at(area, &state, 10, 29, 5), Some(0));
+    }
 }
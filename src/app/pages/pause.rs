@@ -1,11 +1,17 @@
 use std::fmt;
 
-use ratatui::widgets::ListState;
+use ratatui::{layout::Rect, widgets::ListState};
 
-// Available menu options when first running the application
+use super::list_item_at;
+
+// Available options while a test is paused
 #[derive(Debug)]
 pub enum PauseOptions {
     Resume,
+    Restart,
+    NewPrompt,
+    Settings,
+    Menu,
     Quit,
 }
 
@@ -14,7 +20,11 @@ impl fmt::Display for PauseOptions {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PauseOptions::Resume => write!(f, "Resume"),
-            PauseOptions::Quit => write!(f, "Quit"),
+            PauseOptions::Restart => write!(f, "Restart"),
+            PauseOptions::NewPrompt => write!(f, "New prompt"),
+            PauseOptions::Settings => write!(f, "Settings"),
+            PauseOptions::Menu => write!(f, "Menu"),
+            PauseOptions::Quit => write!(f, "Quit app"),
         }
     }
 }
@@ -23,6 +33,16 @@ impl fmt::Display for PauseOptions {
 pub struct Pause {
     pub options: Vec<PauseOptions>,
     pub current_selection: ListState,
+    // where the list was last drawn, used to find the item under a mouse click
+    pub area: Rect,
+    // why the test was paused when it was not the user, shown above the options
+    pub message: Option<String>,
+}
+
+impl Default for Pause {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Pause {
@@ -31,8 +51,17 @@ impl Pause {
         let mut state = ListState::default();
         state.select_first();
         Self {
-            options: vec![PauseOptions::Resume, PauseOptions::Quit],
+            options: vec![
+                PauseOptions::Resume,
+                PauseOptions::Restart,
+                PauseOptions::NewPrompt,
+                PauseOptions::Settings,
+                PauseOptions::Menu,
+                PauseOptions::Quit,
+            ],
             current_selection: state,
+            area: Rect::default(),
+            message: None,
         }
     }
 
@@ -40,6 +69,17 @@ impl Pause {
         self.current_selection.select(None);
     }
 
+    /// Index of the item drawn at `row`, if any.
+    pub fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        list_item_at(
+            self.area,
+            &self.current_selection,
+            self.options.len(),
+            column,
+            row,
+        )
+    }
+
     pub fn select_next(&mut self) {
         self.current_selection.select_next();
     }
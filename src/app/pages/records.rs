@@ -0,0 +1,24 @@
+use crate::{
+    history::{History, HistoryRecord},
+    stats::{personal_bests, PbCategory},
+};
+
+/// Records page, listing the personal best of every category.
+#[derive(Debug, Default)]
+pub struct Records {
+    pub bests: Vec<(PbCategory, HistoryRecord)>,
+}
+
+impl Records {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recomputes the personal bests from every test in the history.
+    pub fn refresh(&mut self, history: &History) {
+        self.bests = personal_bests(&history.records)
+            .into_iter()
+            .map(|(category, record)| (category, record.clone()))
+            .collect();
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+
+use ratatui::{layout::Rect, widgets::ListState};
+
+use super::list_item_at;
+
+// Available options after finishing a test
+#[derive(Debug)]
+pub enum ResultsOptions {
+    NextTest,
+    Retry,
+    PracticeMissed,
+    Menu,
+}
+
+impl fmt::Display for ResultsOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResultsOptions::NextTest => write!(f, "Next test"),
+            ResultsOptions::Retry => write!(f, "Retry same text"),
+            ResultsOptions::PracticeMissed => write!(f, "Practice missed words"),
+            ResultsOptions::Menu => write!(f, "Menu"),
+        }
+    }
+}
+
+/// Page shown after a test, with its stats and whether it set a personal best.
+#[derive(Debug)]
+pub struct Results {
+    pub options: Vec<ResultsOptions>,
+    pub current_selection: ListState,
+    // where the list was last drawn, used to find the item under a mouse click
+    pub area: Rect,
+    // the test beat every earlier test of its category
+    pub new_pb: bool,
+    // WPM of the personal best the test was compared against, if there was one
+    pub previous_best: Option<f32>,
+    // words mistyped in the test
+    pub missed_words: Vec<String>,
+    // keystroke timing was implausible, so the test cannot set a personal best
+    pub flagged: bool,
+}
+
+impl Default for Results {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Results {
+    pub fn new() -> Self {
+        // initialize state as the first item in the list
+        let mut state = ListState::default();
+        state.select_first();
+        Self {
+            options: vec![
+                ResultsOptions::NextTest,
+                ResultsOptions::Retry,
+                ResultsOptions::Menu,
+            ],
+            current_selection: state,
+            area: Rect::default(),
+            new_pb: false,
+            previous_best: None,
+            missed_words: vec![],
+            flagged: false,
+        }
+    }
+
+    /// Shows the results of a test that mistyped `missed_words`, practicing them is
+    /// only offered when there are any.
+    pub fn show(&mut self, missed_words: Vec<String>) {
+        self.options = vec![ResultsOptions::NextTest, ResultsOptions::Retry];
+        if !missed_words.is_empty() {
+            self.options.push(ResultsOptions::PracticeMissed);
+        }
+        self.options.push(ResultsOptions::Menu);
+        self.missed_words = missed_words;
+        self.current_selection.select_first();
+    }
+
+    /// Index of the item drawn at `row`, if any.
+    pub fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        list_item_at(
+            self.area,
+            &self.current_selection,
+            self.options.len(),
+            column,
+            row,
+        )
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt;
+
+use ratatui::widgets::ListState;
+
+use super::Pages;
+use crate::{
+    theme::Theme,
+    typing::{Caret, ErrorMode, IdleAction, LanguagePrompt, Mode, PromptSettings, TIMES},
+};
+
+// Every setting that can be changed from the settings page
+#[derive(Debug, Clone, Copy)]
+pub enum SettingsOptions {
+    WordCount,
+    WordPresets,
+    Mode,
+    Time,
+    Language,
+    Theme,
+    Caret,
+    Punctuation,
+    Capitalization,
+    Numbers,
+    Zen,
+    Adaptive,
+    AdaptiveRatio,
+    ErrorMode,
+    MinAccuracy,
+    Sounds,
+    IdleTimeout,
+    IdleAction,
+}
+
+// We implement Display for SettingsOptions to allow for use of the to_string fn. Bit of a hack
+impl fmt::Display for SettingsOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsOptions::WordCount => write!(f, "Word count"),
+            SettingsOptions::WordPresets => write!(f, "WC presets"),
+            SettingsOptions::Mode => write!(f, "Mode"),
+            SettingsOptions::Time => write!(f, "Time"),
+            SettingsOptions::Language => write!(f, "Language"),
+            SettingsOptions::Theme => write!(f, "Theme"),
+            SettingsOptions::Caret => write!(f, "Caret"),
+            SettingsOptions::Punctuation => write!(f, "Punctuation"),
+            SettingsOptions::Capitalization => write!(f, "Capitalization"),
+            SettingsOptions::Numbers => write!(f, "Numbers"),
+            SettingsOptions::Zen => write!(f, "Zen"),
+            SettingsOptions::Adaptive => write!(f, "Adaptive"),
+            SettingsOptions::AdaptiveRatio => write!(f, "Adaptive mix"),
+            SettingsOptions::ErrorMode => write!(f, "Errors"),
+            SettingsOptions::MinAccuracy => write!(f, "Min accuracy"),
+            SettingsOptions::Sounds => write!(f, "Sounds"),
+            SettingsOptions::IdleTimeout => write!(f, "Idle timeout"),
+            SettingsOptions::IdleAction => write!(f, "When idle"),
+        }
+    }
+}
+
+impl SettingsOptions {
+    /// Current value of this option, formatted for display.
+    pub fn value(&self, settings: &PromptSettings) -> String {
+        match self {
+            SettingsOptions::WordCount => settings.wc.to_string(),
+            SettingsOptions::WordPresets => settings
+                .wc_presets
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            SettingsOptions::Mode => settings.mode.to_string(),
+            SettingsOptions::Time => format!("{}s", settings.time),
+            SettingsOptions::Language => settings.language.clone(),
+            SettingsOptions::Theme => settings.theme.to_string(),
+            SettingsOptions::Caret => settings.caret.to_string(),
+            SettingsOptions::Punctuation => settings.punctuation.to_string(),
+            SettingsOptions::Capitalization => settings.capitalization.to_string(),
+            SettingsOptions::Numbers => settings.numbers.to_string(),
+            SettingsOptions::Zen => settings.zen.to_string(),
+            SettingsOptions::Adaptive => settings.adaptive.to_string(),
+            SettingsOptions::AdaptiveRatio => format!("{}%", settings.adaptive_ratio),
+            SettingsOptions::ErrorMode => settings.error_mode.to_string(),
+            SettingsOptions::MinAccuracy => match settings.min_accuracy {
+                0 => "off".to_string(),
+                x => format!("{}%", x),
+            },
+            SettingsOptions::Sounds => settings.sounds.to_string(),
+            SettingsOptions::IdleTimeout => match settings.idle_timeout {
+                0 => "off".to_string(),
+                x => format!("{}s", x),
+            },
+            SettingsOptions::IdleAction => settings.idle_action.to_string(),
+        }
+    }
+
+    /// Moves this option to its next (or previous) value, toggling booleans.
+    pub fn adjust(&self, settings: &mut PromptSettings, forward: bool) {
+        match self {
+            SettingsOptions::WordCount => {
+                if forward {
+                    settings.next_wc()
+                } else {
+                    settings.previous_wc()
+                }
+            }
+            SettingsOptions::WordPresets => {}
+            SettingsOptions::Mode => settings.mode = cycle(&Mode::ALL, &settings.mode, forward),
+            SettingsOptions::Time => settings.time = cycle(&TIMES, &settings.time, forward),
+            SettingsOptions::Language => {
+                let languages = LanguagePrompt::available();
+                settings.language =
+                    cycle(&languages, &settings.language.as_str(), forward).to_string();
+            }
+            SettingsOptions::Theme => settings.theme = cycle(&Theme::ALL, &settings.theme, forward),
+            SettingsOptions::Caret => settings.caret = cycle(&Caret::ALL, &settings.caret, forward),
+            SettingsOptions::Punctuation => settings.toggle_punctuation(),
+            SettingsOptions::Capitalization => settings.toggle_capitalization(),
+            SettingsOptions::Numbers => settings.toggle_numbers(),
+            SettingsOptions::Zen => settings.toggle_zen(),
+            SettingsOptions::Adaptive => settings.toggle_adaptive(),
+            SettingsOptions::AdaptiveRatio => {}
+            SettingsOptions::ErrorMode => {
+                settings.error_mode = cycle(&ErrorMode::ALL, &settings.error_mode, forward)
+            }
+            SettingsOptions::MinAccuracy => {}
+            SettingsOptions::Sounds => settings.toggle_sounds(),
+            SettingsOptions::IdleTimeout => {}
+            SettingsOptions::IdleAction => {
+                settings.idle_action = cycle(&IdleAction::ALL, &settings.idle_action, forward)
+            }
+        }
+    }
+
+    /// Whether the value is entered as text rather than cycled through.
+    pub fn is_editable(&self) -> bool {
+        matches!(
+            self,
+            SettingsOptions::WordCount
+                | SettingsOptions::WordPresets
+                | SettingsOptions::MinAccuracy
+                | SettingsOptions::AdaptiveRatio
+                | SettingsOptions::IdleTimeout
+        )
+    }
+
+    /// Text an edit starts from, the raw value without display formatting.
+    pub fn edit_value(&self, settings: &PromptSettings) -> String {
+        match self {
+            SettingsOptions::MinAccuracy => settings.min_accuracy.to_string(),
+            SettingsOptions::AdaptiveRatio => settings.adaptive_ratio.to_string(),
+            SettingsOptions::IdleTimeout => settings.idle_timeout.to_string(),
+            _ => self.value(settings),
+        }
+    }
+
+    /// Parses text entered for an editable option and applies it.
+    pub fn apply_input(&self, settings: &mut PromptSettings, input: &str) -> Result<(), String> {
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<u32>()
+                .map_err(|_| format!("'{}' is not a number", v.trim()))
+        };
+        match self {
+            SettingsOptions::WordCount => settings.set_wc(parse(input)?),
+            SettingsOptions::MinAccuracy => settings.set_min_accuracy(parse(input)?),
+            SettingsOptions::AdaptiveRatio => settings.set_adaptive_ratio(parse(input)?),
+            SettingsOptions::IdleTimeout => {
+                settings.idle_timeout = parse(input)?;
+                Ok(())
+            }
+            SettingsOptions::WordPresets => settings.set_wc_presets(
+                input
+                    .split(',')
+                    .filter(|v| !v.trim().is_empty())
+                    .map(parse)
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+// picks the value after (or before) `current`, wrapping around. Unknown values go to the first.
+fn cycle<T: PartialEq + Clone>(values: &[T], current: &T, forward: bool) -> T {
+    let len = values.len();
+    let next = match values.iter().position(|v| v == current) {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None => 0,
+    };
+    values[next].clone()
+}
+
+#[derive(Debug)]
+pub struct Settings {
+    pub options: Vec<SettingsOptions>,
+    pub current_selection: ListState,
+    // result of the last save or edit, shown at the bottom of the page
+    pub message: Option<String>,
+    // text being entered for an editable option, `None` when not editing
+    pub input: Option<String>,
+    // page the settings were opened from, returned to when they are closed
+    pub return_to: Pages,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        // initialize state as the first item in the list
+        let mut state = ListState::default();
+        state.select_first();
+        Self {
+            options: vec![
+                SettingsOptions::WordCount,
+                SettingsOptions::WordPresets,
+                SettingsOptions::Mode,
+                SettingsOptions::Time,
+                SettingsOptions::Language,
+                SettingsOptions::Theme,
+                SettingsOptions::Caret,
+                SettingsOptions::Punctuation,
+                SettingsOptions::Capitalization,
+                SettingsOptions::Numbers,
+                SettingsOptions::Zen,
+                SettingsOptions::Adaptive,
+                SettingsOptions::AdaptiveRatio,
+                SettingsOptions::ErrorMode,
+                SettingsOptions::MinAccuracy,
+                SettingsOptions::Sounds,
+                SettingsOptions::IdleTimeout,
+                SettingsOptions::IdleAction,
+            ],
+            current_selection: state,
+            message: None,
+            input: None,
+            return_to: Pages::Menu,
+        }
+    }
+
+    pub fn selected(&self) -> Option<SettingsOptions> {
+        self.current_selection
+            .selected()
+            .and_then(|i| self.options.get(i).copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_wraps_in_both_directions() {
+        assert_eq!(cycle(&TIMES, &120, true), 15);
+        assert_eq!(cycle(&TIMES, &15, false), 120);
+        assert_eq!(cycle(&TIMES, &7, true), 15);
+    }
+
+    #[test]
+    fn test_apply_input_validates_entered_values() {
+        let mut settings = PromptSettings::new();
+
+        assert!(SettingsOptions::WordCount
+            .apply_input(&mut settings, "abc")
+            .is_err());
+        assert!(SettingsOptions::WordCount
+            .apply_input(&mut settings, "0")
+            .is_err());
+        assert_eq!(
+            SettingsOptions::WordCount.apply_input(&mut settings, "1234"),
+            Ok(())
+        );
+        assert_eq!(settings.wc, 1234);
+
+        assert_eq!(
+            SettingsOptions::WordPresets.apply_input(&mut settings, "100, 10,10,"),
+            Ok(())
+        );
+        assert_eq!(settings.wc_presets, vec![10, 100]);
+    }
+}
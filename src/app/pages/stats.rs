@@ -0,0 +1,167 @@
+use std::fmt;
+
+use chrono::{Duration, Utc};
+
+use crate::typing::Mode;
+use crate::{
+    history::{History, HistoryRecord},
+    stats::counts_for_pb,
+};
+
+// number of tests averaged for the trend line
+pub const MOVING_AVERAGE_WINDOW: usize = 10;
+
+/// Which tests are shown on the history chart.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ChartRange {
+    Last10,
+    #[default]
+    Last100,
+    Last30Days,
+    All,
+}
+
+impl ChartRange {
+    pub const ALL: [ChartRange; 4] = [
+        ChartRange::Last10,
+        ChartRange::Last100,
+        ChartRange::Last30Days,
+        ChartRange::All,
+    ];
+}
+
+impl fmt::Display for ChartRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChartRange::Last10 => write!(f, "last 10"),
+            ChartRange::Last100 => write!(f, "last 100"),
+            ChartRange::Last30Days => write!(f, "last 30 days"),
+            ChartRange::All => write!(f, "all"),
+        }
+    }
+}
+
+/// History page, charting WPM and accuracy over time.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub range: ChartRange,
+    // `None` shows every mode, language or word count
+    pub mode: Option<Mode>,
+    pub language: Option<String>,
+    pub wc: Option<u32>,
+}
+
+// moves to the next value of `values`, where `None` (no filter) comes before the first
+fn next_filter<T: PartialEq + Clone>(values: &[T], current: &Option<T>) -> Option<T> {
+    match current {
+        None => values.first().cloned(),
+        Some(c) => values
+            .iter()
+            .position(|v| v == c)
+            .and_then(|i| values.get(i + 1))
+            .cloned(),
+    }
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn next_range(&mut self) {
+        let i = ChartRange::ALL
+            .iter()
+            .position(|r| *r == self.range)
+            .unwrap_or(0);
+        self.range = ChartRange::ALL[(i + 1) % ChartRange::ALL.len()];
+    }
+
+    pub fn next_mode(&mut self) {
+        self.mode = next_filter(&Mode::ALL, &self.mode);
+    }
+
+    /// Cycles through the languages that appear in the history.
+    pub fn next_language(&mut self, history: &History) {
+        let mut languages: Vec<String> =
+            history.records.iter().map(|r| r.language.clone()).collect();
+        languages.sort();
+        languages.dedup();
+        self.language = next_filter(&languages, &self.language);
+    }
+
+    /// Cycles through the word counts that appear in the history.
+    pub fn next_wc(&mut self, history: &History) {
+        let mut counts: Vec<u32> = history
+            .records
+            .iter()
+            .filter(|r| r.mode == Mode::Words)
+            .map(|r| r.wc)
+            .collect();
+        counts.sort_unstable();
+        counts.dedup();
+        self.wc = next_filter(&counts, &self.wc);
+    }
+
+    fn matches(&self, record: &HistoryRecord) -> bool {
+        // drills, lessons and practice use restricted prompts, so they are not comparable to regular tests
+        counts_for_pb(record)
+            && self.mode.is_none_or(|m| record.mode == m)
+            && self.language.as_ref().is_none_or(|l| &record.language == l)
+            && self
+                .wc
+                .is_none_or(|wc| record.mode == Mode::Words && record.wc == wc)
+    }
+
+    /// Records matching the filters and range, oldest first.
+    pub fn visible<'a>(&self, history: &'a History) -> Vec<&'a HistoryRecord> {
+        let mut records: Vec<&HistoryRecord> =
+            history.records.iter().filter(|r| self.matches(r)).collect();
+        records.sort_by_key(|r| r.timestamp);
+        let keep = match self.range {
+            ChartRange::Last10 => 10,
+            ChartRange::Last100 => 100,
+            ChartRange::Last30Days => {
+                let since = Utc::now() - Duration::days(30);
+                records.iter().filter(|r| r.timestamp >= since).count()
+            }
+            ChartRange::All => records.len(),
+        };
+        records.split_off(records.len().saturating_sub(keep))
+    }
+}
+
+/// Trailing moving average of `values` over `window` entries.
+pub fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, _)| {
+            let start = (i + 1).saturating_sub(window);
+            let slice = &values[start..=i];
+            slice.iter().sum::<f64>() / slice.len() as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moving_average_uses_trailing_window() {
+        assert_eq!(
+            moving_average(&[10.0, 20.0, 30.0, 40.0], 2),
+            vec![10.0, 15.0, 25.0, 35.0]
+        );
+    }
+
+    #[test]
+    fn test_filters_cycle_back_to_none() {
+        let mut stats = Stats::new();
+        stats.next_mode();
+        assert_eq!(stats.mode, Some(Mode::Words));
+        stats.next_mode();
+        stats.next_mode();
+        assert_eq!(stats.mode, None);
+    }
+}
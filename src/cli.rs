@@ -1,4 +1,12 @@
-use clap::{ArgAction, Parser};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use clap::{ArgAction, Parser, Subcommand};
+
+use type_fast::{
+    export::{parse_since, ExportFormat},
+    typing::Mode,
+};
 
 #[derive(Parser)]
 #[command(name = "ATTT")]
@@ -9,6 +17,69 @@ pub struct Cli {
     pub skip_menu: bool,
     #[arg(long,action=ArgAction::Set,default_value_t=String::new())]
     pub config: String,
-    #[arg(long,action=ArgAction::Set,default_value_t=25)]
-    pub wc: u32,
+    /// Word count for prompts, overrides the config file
+    #[arg(long,action=ArgAction::Set,value_parser=clap::value_parser!(u32).range(1..=10_000))]
+    pub wc: Option<u32>,
+    /// Seed for the first prompt, the same seed and settings give the same prompt
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Play back key presses from a script instead of reading the keyboard
+    #[arg(long, requires = "headless")]
+    pub input_script: Option<PathBuf>,
+    /// Run without the terminal interface and print the completed tests as json.
+    /// The history is left untouched
+    #[arg(long, requires = "input_script")]
+    pub headless: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Practice specific n-grams, e.g. `drill --ngram th,ing`
+    Drill {
+        /// Comma separated n-grams to practice
+        #[arg(long, required = true, value_delimiter = ',')]
+        ngram: Vec<String>,
+        /// Repeat the n-grams themselves instead of using real words
+        #[arg(long)]
+        synthetic: bool,
+    },
+    /// Write the test history to stdout or a file, e.g. `export --format csv`
+    Export {
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+        /// Only export tests taken on or after this date, e.g. 2024-05-01
+        #[arg(long, value_parser = parse_since)]
+        since: Option<DateTime<Utc>>,
+        /// Only export tests of this mode
+        #[arg(long, value_enum)]
+        mode: Option<Mode>,
+        /// Include the keystroke timeline of every test
+        #[arg(long)]
+        keystrokes: bool,
+        /// File to write to instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Print a summary of the test history without starting the interface
+    Stats {
+        /// Number of recent tests the trend is computed over
+        #[arg(long, default_value_t = 10)]
+        last: usize,
+        /// Print the summary as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Add tests exported from another typing test to the history
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportSource {
+    /// Results csv downloaded from the monkeytype account page
+    Monkeytype { file: PathBuf },
 }
@@ -1,12 +1,81 @@
+use std::{fs, path::PathBuf};
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+use crate::{typing::PromptSettings, AppResult};
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// Milliseconds between ticks when no test is running.
+pub const DEFAULT_TICK_RATE: u64 = 250;
+
+fn default_tick_rate() -> u64 {
+    DEFAULT_TICK_RATE
+}
+
+/// Persistent user configuration, stored as toml in the user's config directory.
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
-    temp: String,
+    #[serde(default)]
+    pub prompt: PromptSettings,
+    // milliseconds between ticks, which drive timers and idle checks
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate: u64,
+    // where the config was loaded from and will be saved to
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            prompt: PromptSettings::default(),
+            tick_rate: DEFAULT_TICK_RATE,
+            path: None,
+        }
+    }
 }
 
 impl Config {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Default location of the config file, `<config dir>/type-fast/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("type-fast").join(CONFIG_FILE))
+    }
+
+    /// Loads the config from `path`, or from [`Config::default_path`] when `path` is empty.
+    /// A missing file is not an error, defaults are used instead.
+    pub fn load(path: &str) -> AppResult<Self> {
+        let path = if path.is_empty() {
+            Self::default_path()
+        } else {
+            Some(PathBuf::from(path))
+        };
+
+        let mut config = match &path {
+            Some(p) if p.exists() => toml::from_str(&fs::read_to_string(p)?)?,
+            _ => Self::new(),
+        };
+        config.prompt.validate();
+        if config.tick_rate == 0 {
+            config.tick_rate = DEFAULT_TICK_RATE;
+        }
+        config.path = path;
+        Ok(config)
+    }
+
+    /// Writes the config back to the file it was loaded from.
+    pub fn save(&self) -> AppResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
 }
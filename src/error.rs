@@ -0,0 +1,30 @@
+use thiserror::Error;
+
+/// Errors the app can run into. They are shown in a popup while the interface runs.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("invalid config file: {0}")]
+    Config(#[from] toml::de::Error),
+    #[error("failed to write the config file: {0}")]
+    ConfigWrite(#[from] toml::ser::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error("failed to start logging: {0}")]
+    Logger(String),
+    /// Neither the selected nor the default word bank could be loaded.
+    #[error("no word bank named '{0}' is available")]
+    Language(String),
+    /// Input given by the user, such as n-grams or an input script, could not be used.
+    #[error("{0}")]
+    Invalid(String),
+    /// A list entry past the end of the list was chosen.
+    #[error("there is no option {0} in this list")]
+    NoOption(usize),
+    /// Every sender of events is gone, e.g. an input script was played to the end.
+    #[error("no events left")]
+    EventsClosed,
+}
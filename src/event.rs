@@ -1,13 +1,16 @@
 use std::time::Duration;
 
-use crossterm::event::{Event as CrosstermEvent, KeyEvent, MouseEvent};
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers, MouseEvent};
 use futures::{FutureExt, StreamExt};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
-use crate::app::AppResult;
+use crate::{app::AppResult, Error};
+
+// time between key presses of a script, unless it sets a delay
+const SCRIPT_KEY_DELAY_MS: u64 = 200;
 
 /// Terminal events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Event {
     /// Terminal tick.
     Tick,
@@ -17,29 +20,37 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// The terminal lost focus.
+    FocusLost,
+    /// The terminal gained focus.
+    FocusGained,
+    /// Text pasted into the terminal.
+    Paste(String),
+    /// Time passing on a manual clock, sent by input scripts.
+    Wait(Duration),
 }
 
 /// Terminal event handler.
-#[allow(dead_code)]
 #[derive(Debug)]
 pub struct EventHandler {
-    /// Event sender channel.
-    sender: mpsc::UnboundedSender<Event>,
     /// Event receiver channel.
     receiver: mpsc::UnboundedReceiver<Event>,
-    /// Event handler thread.
-    handler: tokio::task::JoinHandle<()>,
+    /// Event handler thread, `None` when events come from a channel.
+    #[allow(dead_code)]
+    handler: Option<tokio::task::JoinHandle<()>>,
+    /// Milliseconds between ticks, read by the handler thread.
+    tick_rate: Option<watch::Sender<u64>>,
 }
 
 impl EventHandler {
     /// Constructs a new instance of [`EventHandler`].
     pub fn new(tick_rate: u64) -> Self {
-        let tick_rate = Duration::from_millis(tick_rate);
+        let (tick_rate, mut rate) = watch::channel(tick_rate);
         let (sender, receiver) = mpsc::unbounded_channel();
         let _sender = sender.clone();
         let handler = tokio::spawn(async move {
             let mut reader = crossterm::event::EventStream::new();
-            let mut tick = tokio::time::interval(tick_rate);
+            let mut tick = tokio::time::interval(Duration::from_millis(*rate.borrow()));
             loop {
                 let tick_delay = tick.tick();
                 let crossterm_event = reader.next().fuse();
@@ -47,51 +58,210 @@ impl EventHandler {
                   _ = _sender.closed() => {
                     break;
                   }
+                  Ok(()) = rate.changed() => {
+                    tick = tokio::time::interval(Duration::from_millis(*rate.borrow_and_update()));
+                  }
                   _ = tick_delay => {
-                    _sender.send(Event::Tick).unwrap();
+                    // the receiver is gone once the app has exited
+                    if _sender.send(Event::Tick).is_err() {
+                      break;
+                    }
                   }
                   Some(Ok(evt)) = crossterm_event => {
-                    match evt {
+                    let event = match evt {
                       CrosstermEvent::Key(key) => {
-                        if key.kind == crossterm::event::KeyEventKind::Press {
-                          _sender.send(Event::Key(key)).unwrap();
+                        if key.kind != crossterm::event::KeyEventKind::Press {
+                          continue;
                         }
+                        Event::Key(key)
                       },
-                      CrosstermEvent::Mouse(mouse) => {
-                        _sender.send(Event::Mouse(mouse)).unwrap();
-                      },
-                      CrosstermEvent::Resize(x, y) => {
-                        _sender.send(Event::Resize(x, y)).unwrap();
-                      },
-                      CrosstermEvent::FocusLost => {
-                      },
-                      CrosstermEvent::FocusGained => {
-                      },
-                      CrosstermEvent::Paste(_) => {
-                      },
+                      CrosstermEvent::Mouse(mouse) => Event::Mouse(mouse),
+                      CrosstermEvent::Resize(x, y) => Event::Resize(x, y),
+                      CrosstermEvent::FocusLost => Event::FocusLost,
+                      CrosstermEvent::FocusGained => Event::FocusGained,
+                      CrosstermEvent::Paste(text) => Event::Paste(text),
+                    };
+                    if _sender.send(event).is_err() {
+                      break;
                     }
                   }
                 };
             }
         });
         Self {
-            sender,
             receiver,
-            handler,
+            handler: Some(handler),
+            tick_rate: Some(tick_rate),
+        }
+    }
+
+    /// Changes the milliseconds between ticks, events from a channel have no ticks.
+    pub fn set_tick_rate(&self, tick_rate: u64) {
+        if let Some(sender) = &self.tick_rate {
+            sender.send_if_modified(|rate| {
+                let changed = *rate != tick_rate;
+                *rate = tick_rate;
+                changed
+            });
+        }
+    }
+
+    /// An event handler fed from memory instead of the terminal. It runs out of events
+    /// once every sender is dropped.
+    pub fn channel() -> (Self, mpsc::UnboundedSender<Event>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let handler = Self {
+            receiver,
+            handler: None,
+            tick_rate: None,
+        };
+        (handler, sender)
+    }
+
+    /// An event handler that plays back `events`, then runs out.
+    pub fn from_events(events: impl IntoIterator<Item = Event>) -> Self {
+        let (handler, sender) = Self::channel();
+        for event in events {
+            // the receiver is alive, held by the handler
+            let _ = sender.send(event);
         }
+        handler
     }
 
     /// Receive the next event from the handler thread.
     ///
     /// This function will always block the current thread if
     /// there is no data available and it's possible for more data to be sent.
+    /// Fails once there are no events left.
     pub async fn next(&mut self) -> AppResult<Event> {
-        self.receiver
-            .recv()
-            .await
-            .ok_or(Box::new(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "This is an IO error",
-            )))
+        self.receiver.recv().await.ok_or(Error::EventsClosed)
+    }
+}
+
+/// Parses an input script into the events it plays back.
+///
+/// Each line is typed as written. `<name>` presses a named key: `enter`, `esc`, `space`,
+/// `backspace`, `tab`, `left`, `right`, `up`, `down`, `home`, `end`, `lt` for `<`, or
+/// `alt-c` for alt and a character. `<tick>` sends a tick, `<wait ms>` moves the clock
+/// forward and ticks, and `<delay ms>` sets the time between key presses (200ms by default).
+/// Blank lines and lines starting with `#` are skipped.
+pub fn parse_script(script: &str) -> Result<Vec<Event>, String> {
+    let mut events = vec![];
+    let mut delay = Duration::from_millis(SCRIPT_KEY_DELAY_MS);
+    let key = |code: KeyCode, modifiers: KeyModifiers, delay: Duration| {
+        [
+            Event::Wait(delay),
+            Event::Key(KeyEvent::new(code, modifiers)),
+        ]
+    };
+    for (number, line) in script.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            if c != '<' {
+                events.extend(key(KeyCode::Char(c), KeyModifiers::NONE, delay));
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+            let Some(end) = rest.find('>') else {
+                return Err(format!("line {}: unclosed '<'", number + 1));
+            };
+            let name = &rest[1..end];
+            rest = &rest[end + 1..];
+            let ms = |value: &str| {
+                value
+                    .trim()
+                    .parse::<u64>()
+                    .map(Duration::from_millis)
+                    .map_err(|_| format!("line {}: '{}' is not a number", number + 1, value))
+            };
+            let code = match name {
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "lt" => KeyCode::Char('<'),
+                "tick" => {
+                    events.push(Event::Tick);
+                    continue;
+                }
+                _ => {
+                    if let Some(value) = name.strip_prefix("wait ") {
+                        events.extend([Event::Wait(ms(value)?), Event::Tick]);
+                    } else if let Some(value) = name.strip_prefix("delay ") {
+                        delay = ms(value)?;
+                    } else if let Some(c) = name.strip_prefix("alt-").filter(|c| c.len() == 1) {
+                        let c = c.chars().next().unwrap_or_default();
+                        events.extend(key(KeyCode::Char(c), KeyModifiers::ALT, delay));
+                    } else {
+                        return Err(format!("line {}: unknown key <{}>", number + 1, name));
+                    }
+                    continue;
+                }
+            };
+            events.extend(key(code, KeyModifiers::NONE, delay));
+        }
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_script_types_text_and_named_keys() {
+        let events =
+            parse_script("# start a test\n<enter>\n<delay 100>a b<lt>\n<wait 5000>\n").unwrap();
+        let keys: Vec<String> = events
+            .iter()
+            .map(|e| match e {
+                Event::Key(k) => format!("{}", k.code),
+                Event::Wait(d) => format!("wait {}", d.as_millis()),
+                Event::Tick => "tick".to_string(),
+                _ => "other".to_string(),
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                "wait 200",
+                "Enter",
+                "wait 100",
+                "a",
+                "wait 100",
+                "Space",
+                "wait 100",
+                "b",
+                "wait 100",
+                "<",
+                "wait 5000",
+                "tick"
+            ]
+        );
+        assert!(parse_script("<jump>").is_err());
+        assert!(parse_script("<wait soon>").is_err());
+    }
+
+    #[test]
+    fn test_channel_runs_out_once_its_senders_are_dropped() {
+        let (mut events, sender) = EventHandler::channel();
+        sender.send(Event::Tick).unwrap();
+        sender.send(Event::FocusLost).unwrap();
+        drop(sender);
+        futures::executor::block_on(async {
+            assert!(matches!(events.next().await, Ok(Event::Tick)));
+            assert!(matches!(events.next().await, Ok(Event::FocusLost)));
+            assert!(matches!(events.next().await, Err(Error::EventsClosed)));
+        });
     }
 }
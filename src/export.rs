@@ -0,0 +1,213 @@
+use std::io::Write;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::{
+    history::HistoryRecord,
+    typing::{ErrorMode, Mode},
+    AppResult,
+};
+
+/// File formats the history can be exported as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "tui", derive(clap::ValueEnum))]
+pub enum ExportFormat {
+    /// One row per test, with the keystroke timeline as a json column when included
+    Csv,
+    /// A single json array of tests
+    Json,
+    /// One json test per line, the format of the history file
+    Jsonl,
+}
+
+/// Which tests are exported.
+#[derive(Debug, Default, Clone)]
+pub struct ExportFilter {
+    // only tests taken on or after this time
+    pub since: Option<DateTime<Utc>>,
+    pub mode: Option<Mode>,
+}
+
+impl ExportFilter {
+    pub fn matches(&self, record: &HistoryRecord) -> bool {
+        self.since.is_none_or(|since| record.timestamp >= since)
+            && self.mode.is_none_or(|mode| record.mode == mode)
+    }
+}
+
+/// Parses a `--since` value, either a date such as `2024-05-01` or an RFC 3339 timestamp.
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| format!("'{}' is not a date, expected e.g. 2024-05-01", value))
+}
+
+// a flat test record, csv has no nested values
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    timestamp: String,
+    mode: Mode,
+    wc: u32,
+    time: u32,
+    language: &'a str,
+    punctuation: bool,
+    capitalization: bool,
+    numbers: bool,
+    error_mode: ErrorMode,
+    wpm: f32,
+    accuracy: f32,
+    awpm: f32,
+    duration: f32,
+    failed: bool,
+    flagged: bool,
+    consistency: Option<f32>,
+    seed: Option<u64>,
+    drill: String,
+    practice: String,
+    lesson: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keystrokes: Option<String>,
+}
+
+impl<'a> CsvRow<'a> {
+    fn new(record: &'a HistoryRecord, keystrokes: bool) -> AppResult<Self> {
+        Ok(Self {
+            timestamp: record.timestamp.to_rfc3339(),
+            mode: record.mode,
+            wc: record.wc,
+            time: record.time,
+            language: &record.language,
+            punctuation: record.punctuation,
+            capitalization: record.capitalization,
+            numbers: record.numbers,
+            error_mode: record.error_mode,
+            wpm: record.wpm,
+            accuracy: record.accuracy,
+            awpm: record.awpm,
+            duration: record.duration,
+            failed: record.failed,
+            flagged: record.flagged,
+            consistency: record.consistency,
+            seed: record.seed,
+            drill: record
+                .drill
+                .as_ref()
+                .map(|d| d.join(","))
+                .unwrap_or_default(),
+            practice: record
+                .practice
+                .as_ref()
+                .map(|p| p.join(","))
+                .unwrap_or_default(),
+            lesson: record.lesson.as_deref().unwrap_or_default(),
+            keystrokes: if keystrokes {
+                Some(serde_json::to_string(&record.keystrokes)?)
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// Writes the tests in `records` matching `filter` to `out`. The keystroke timeline of
+/// every test is only included when `keystrokes` is set, as it makes up most of the size.
+pub fn export<'a>(
+    records: impl IntoIterator<Item = &'a HistoryRecord>,
+    out: impl Write,
+    format: ExportFormat,
+    filter: &ExportFilter,
+    keystrokes: bool,
+) -> AppResult<()> {
+    let records: Vec<HistoryRecord> = records
+        .into_iter()
+        .filter(|r| filter.matches(r))
+        .map(|r| {
+            let mut record = r.clone();
+            if !keystrokes {
+                record.keystrokes.clear();
+            }
+            record
+        })
+        .collect();
+
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for record in &records {
+                writer.serialize(CsvRow::new(record, keystrokes)?)?;
+            }
+            writer.flush()?;
+        }
+        ExportFormat::Json => {
+            let mut out = out;
+            serde_json::to_writer_pretty(&mut out, &records)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Jsonl => {
+            let mut out = out;
+            for record in &records {
+                writeln!(out, "{}", serde_json::to_string(record)?)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(mode: Mode) -> HistoryRecord {
+        HistoryRecord::sample(mode, 72.5, &[('a', 'a', 0)])
+    }
+
+    #[test]
+    fn test_csv_export_filters_by_mode_and_includes_keystrokes_on_request() {
+        let records = [record(Mode::Words), record(Mode::Time)];
+        let filter = ExportFilter {
+            since: None,
+            mode: Some(Mode::Time),
+        };
+
+        let mut out = vec![];
+        export(&records, &mut out, ExportFormat::Csv, &filter, false).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.lines().next().unwrap().ends_with("lesson"));
+        assert!(csv.contains(",time,"));
+
+        let mut out = vec![];
+        export(&records, &mut out, ExportFormat::Csv, &filter, true).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.lines().next().unwrap().ends_with("keystrokes"));
+    }
+
+    #[test]
+    fn test_json_export_includes_keystrokes_on_request() {
+        let records = [record(Mode::Words)];
+        let filter = ExportFilter::default();
+        for format in [ExportFormat::Json, ExportFormat::Jsonl] {
+            let mut out = vec![];
+            export(&records, &mut out, format, &filter, false).unwrap();
+            assert!(!String::from_utf8(out).unwrap().contains("keystrokes"));
+
+            let mut out = vec![];
+            export(&records, &mut out, format, &filter, true).unwrap();
+            assert!(String::from_utf8(out).unwrap().contains("keystrokes"));
+        }
+    }
+
+    #[test]
+    fn test_parse_since_accepts_dates_and_timestamps() {
+        assert_eq!(
+            parse_since("2024-05-01").unwrap().to_rfc3339(),
+            "2024-05-01T00:00:00+00:00"
+        );
+        assert!(parse_since("2024-05-01T10:00:00+02:00").is_ok());
+        assert!(parse_since("yesterday").is_err());
+    }
+}
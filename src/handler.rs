@@ -1,121 +1,253 @@
-use crate::app::{App, AppResult, Pages};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::{
+    app::{Action, App, AppResult, Pages, Selection},
+    event::{Event, EventHandler},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use log::trace;
+use ratatui::layout::Position;
+
+/// Handles a terminal event and updates the state of [`App`].
+pub fn handle_event(event: Event, app: &mut App) -> AppResult<()> {
+    match event {
+        Event::Tick => app.dispatch(Action::Tick),
+        Event::Wait(by) => app.dispatch(Action::AdvanceClock(by)),
+        Event::Key(key_event) => handle_key_events(key_event, app)?,
+        Event::Mouse(mouse_event) => handle_mouse_events(mouse_event, app),
+        Event::Resize(columns, rows) => app.dispatch(Action::Resize(columns, rows)),
+        Event::FocusLost => app.dispatch(Action::FocusLost),
+        // the test stays paused until the user resumes it
+        Event::FocusGained => {}
+        Event::Paste(text) => handle_paste(&text, app),
+    }
+    Ok(())
+}
+
+/// Handles every event of `events` until they run out or the app quits, without drawing
+/// the app. This is how headless runs play an input script.
+pub async fn play(events: &mut EventHandler, app: &mut App) -> AppResult<()> {
+    while app.running {
+        // the run ends when the events do
+        let Ok(event) = events.next().await else {
+            break;
+        };
+        handle_event(event, app)?;
+    }
+    Ok(())
+}
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
-    match app.current_page {
-        Pages::Menu => handle_menu(key_event, app),
-        Pages::Typing => handle_typing(key_event, app),
-        Pages::Pause => handle_pause(key_event, app),
-        Pages::Stats => todo!(),
+    // any key closes an error popup, without reaching the page beneath it
+    if app.error.is_some() {
+        app.dispatch(Action::DismissError);
+        return Ok(());
+    }
+    let action = match app.current_page {
+        Pages::Menu => handle_menu(key_event),
+        Pages::Typing => handle_typing(key_event),
+        Pages::Pause => handle_pause(key_event),
+        Pages::Settings => handle_settings(key_event, app),
+        Pages::Heatmap => handle_heatmap(key_event),
+        Pages::Drill => handle_drill(key_event),
+        Pages::Lessons => handle_lessons(key_event),
+        Pages::Stats => handle_stats(key_event),
+        Pages::Results => handle_results(key_event),
+        Pages::Records => handle_records(key_event),
+    };
+    if let Some(action) = action {
+        app.dispatch(action);
     }
 
     Ok(())
 }
 
-fn handle_menu(key_event: KeyEvent, app: &mut App) {
+/// Handles mouse clicks and scrolling, which select and move through the menu, pause and
+/// results lists, and toggle the settings bar above the prompt.
+pub fn handle_mouse_events(mouse_event: MouseEvent, app: &mut App) {
+    let (column, row) = (mouse_event.column, mouse_event.row);
+    let click = mouse_event.kind == MouseEventKind::Down(MouseButton::Left);
+    if app.error.is_some() {
+        if click {
+            app.dispatch(Action::DismissError);
+        }
+        return;
+    }
+    match mouse_event.kind {
+        MouseEventKind::ScrollDown => app.dispatch(Action::Select(Selection::Next)),
+        MouseEventKind::ScrollUp => app.dispatch(Action::Select(Selection::Previous)),
+        _ => {}
+    }
+    if !click {
+        return;
+    }
+
+    let item = match app.current_page {
+        Pages::Menu => app.menu.item_at(column, row),
+        Pages::Pause => app.pause_popup.item_at(column, row),
+        Pages::Results => app.results.item_at(column, row),
+        Pages::Typing => {
+            let position = Position::new(column, row);
+            if let Some(i) = app.settings_bar.iter().position(|a| a.contains(position)) {
+                app.dispatch(Action::ToggleSettingsBar(i));
+            }
+            None
+        }
+        _ => None,
+    };
+    if let Some(i) = item {
+        app.dispatch(Action::Select(Selection::Index(i)));
+        app.dispatch(Action::Confirm);
+    }
+}
+
+/// Handles text pasted into the terminal. Pasting only works in text entry fields,
+/// pasted text is refused during a test.
+pub fn handle_paste(text: &str, app: &mut App) {
+    app.dispatch(Action::Paste(text.to_string()));
+}
+
+// movement shared by every page with a list
+fn list_action(code: KeyCode) -> Option<Action> {
+    let selection = match code {
+        KeyCode::Char('j') | KeyCode::Down => Selection::Next,
+        KeyCode::Char('k') | KeyCode::Up => Selection::Previous,
+        KeyCode::Char('g') | KeyCode::Home => Selection::First,
+        KeyCode::Char('G') | KeyCode::End => Selection::Last,
+        KeyCode::Enter => return Some(Action::Confirm),
+        _ => return None,
+    };
+    Some(Action::Select(selection))
+}
+
+fn handle_menu(key_event: KeyEvent) -> Option<Action> {
     match key_event.code {
         // Exit application on `ESC`
-        KeyCode::Esc => {
-            app.quit();
-        }
+        KeyCode::Esc => Some(Action::Quit),
+        KeyCode::Char('h') | KeyCode::Left => Some(Action::Select(Selection::None)),
+        code => list_action(code),
+    }
+}
 
-        KeyCode::Char('h') | KeyCode::Left => app.menu.select_none(),
-        KeyCode::Char('j') | KeyCode::Down => app.menu.select_next(),
-        KeyCode::Char('k') | KeyCode::Up => app.menu.select_previous(),
-        KeyCode::Char('g') | KeyCode::Home => app.menu.select_first(),
-        KeyCode::Char('G') | KeyCode::End => app.menu.select_last(),
-        KeyCode::Enter => app.select_menu_option(),
+fn handle_pause(key_event: KeyEvent) -> Option<Action> {
+    match key_event.code {
+        // Resume the test on `ESC`
+        KeyCode::Esc => Some(Action::Resume),
+        KeyCode::Char('r') => Some(Action::Retry),
+        KeyCode::Char('q') => Some(Action::Navigate(Pages::Menu)),
+        KeyCode::Char('h') | KeyCode::Left => Some(Action::Select(Selection::None)),
+        code => list_action(code),
+    }
+}
 
-        _ => {}
+fn handle_lessons(key_event: KeyEvent) -> Option<Action> {
+    match key_event.code {
+        KeyCode::Esc => Some(Action::Navigate(Pages::Menu)),
+        code => list_action(code),
     }
 }
-fn handle_pause(key_event: KeyEvent, app: &mut App) {
+
+fn handle_drill(key_event: KeyEvent) -> Option<Action> {
     match key_event.code {
-        // Exit application on `ESC`
-        KeyCode::Esc => {
-            app.unpause();
-        }
-        KeyCode::Char('q') => {
-            app.unpause();
-            app.current_page = Pages::Menu;
-        }
+        KeyCode::Esc => Some(Action::Navigate(Pages::Menu)),
+        KeyCode::Enter => Some(Action::Confirm),
+        KeyCode::Tab => Some(Action::ToggleSynthetic),
+        KeyCode::Backspace => Some(Action::Backspace),
+        KeyCode::Char(c) => Some(Action::Input(c)),
+        _ => None,
+    }
+}
 
-        _ => {}
+fn handle_results(key_event: KeyEvent) -> Option<Action> {
+    match key_event.code {
+        KeyCode::Esc => Some(Action::Navigate(Pages::Menu)),
+        code => list_action(code),
     }
 }
 
-fn handle_typing(key_event: KeyEvent, app: &mut App) {
-    // TODO could extend alphabet to include other necessary characters
-    let alphabet = (b'A'..=b'z') // Start as u8
-        .filter_map(|c| {
-            let c = c as char; // Convert to char
-            if c.is_alphabetic() {
-                Some(c)
-            } else {
-                None
-            } // Filter only alphabetic chars
-        })
-        .collect::<Vec<_>>();
+fn handle_records(key_event: KeyEvent) -> Option<Action> {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => Some(Action::Navigate(Pages::Menu)),
+        _ => None,
+    }
+}
 
-    let mut prompt_complete = false;
+fn handle_stats(key_event: KeyEvent) -> Option<Action> {
     match key_event.code {
-        // Exit application on `ESC`
-        KeyCode::Esc => {
-            app.pause();
-        }
+        KeyCode::Esc | KeyCode::Char('q') => Some(Action::Navigate(Pages::Menu)),
+        KeyCode::Char('r') => Some(Action::NextStatsRange),
+        KeyCode::Char('m') => Some(Action::NextStatsMode),
+        KeyCode::Char('l') => Some(Action::NextStatsLanguage),
+        KeyCode::Char('w') => Some(Action::NextStatsWc),
+        _ => None,
+    }
+}
+
+fn handle_heatmap(key_event: KeyEvent) -> Option<Action> {
+    match key_event.code {
+        KeyCode::Esc | KeyCode::Char('q') => Some(Action::Navigate(Pages::Menu)),
+        KeyCode::Char('l') => Some(Action::NextHeatmapLayout),
+        KeyCode::Char('m') => Some(Action::ToggleHeatmapMetric),
+        _ => None,
+    }
+}
+
+fn handle_settings(key_event: KeyEvent, app: &App) -> Option<Action> {
+    if app.settings.input.is_some() {
+        return match key_event.code {
+            KeyCode::Esc => Some(Action::CancelInput),
+            KeyCode::Enter => Some(Action::Confirm),
+            KeyCode::Backspace => Some(Action::Backspace),
+            KeyCode::Char(c) => Some(Action::Input(c)),
+            _ => None,
+        };
+    }
+
+    match key_event.code {
+        // Save and return to the menu on `ESC`
+        KeyCode::Esc => Some(Action::SaveSettings),
+        // Leave without saving, changes still apply to this session
+        KeyCode::Char('q') => Some(Action::LeaveSettings),
 
-        KeyCode::Char(' ') => {
-            prompt_complete = app.typing.input('•');
+        KeyCode::Char('h') | KeyCode::Left => Some(Action::AdjustSetting(false)),
+        KeyCode::Char('l') | KeyCode::Right | KeyCode::Char(' ') => {
+            Some(Action::AdjustSetting(true))
         }
+        code => list_action(code),
+    }
+}
+
+fn handle_typing(key_event: KeyEvent) -> Option<Action> {
+    match key_event.code {
+        // Pause the test on `ESC`
+        KeyCode::Esc => Some(Action::Pause),
+
+        KeyCode::Char(' ') => Some(Action::Input('•')),
+
+        // Control modifier was not working for me in this case
+        KeyCode::Char(ch) if key_event.modifiers == KeyModifiers::ALT => match ch {
+            '1'..='4' => Some(Action::ToggleSettingsBar(ch as usize - '1' as usize)),
+            _ => None,
+        },
 
         KeyCode::Char(ch) => {
             if (key_event.modifiers == KeyModifiers::NONE
                 || key_event.modifiers == KeyModifiers::SHIFT)
-                && alphabet.contains(&ch)
+                && ch.is_ascii_graphic()
             {
-                // todo all input from user for all alphabetic character while in typing mode.
                 trace!(target:"Input", "User input char {}", ch);
-                prompt_complete = app.typing.input(ch);
-            }
-
-            // Control modifier was not working for me in this case
-            if key_event.modifiers == KeyModifiers::ALT {
-                match ch {
-                    '1' => {
-                        app.typing.settings.borrow_mut().next_wc();
-                    }
-                    '2' => {
-                        app.typing.settings.borrow_mut().toggle_capitalization();
-                    }
-                    '3' => {
-                        app.typing.settings.borrow_mut().toggle_punctuation();
-                    }
-                    '4' => {
-                        app.typing.settings.borrow_mut().toggle_zen();
-                    }
-
-                    _ => {}
-                }
+                return Some(Action::Input(ch));
             }
+            None
         }
 
+        KeyCode::Backspace => Some(Action::Backspace),
+
         // restart current prompt
-        KeyCode::Left => {
-            app.typing.reset();
-        }
+        KeyCode::Left => Some(Action::Retry),
         // skip to new prompt
-        KeyCode::Right => {
-            app.new_prompt();
-        }
+        KeyCode::Right => Some(Action::NewPrompt),
 
         // Other handlers you could add here.
-        _ => {}
-    }
-
-    app.typing.construct_text();
-    if prompt_complete {
-        app.new_prompt();
+        _ => None,
     }
 }
@@ -0,0 +1,246 @@
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    stats::implausible_timing,
+    typing::{ErrorMode, Keystroke, Mode, PromptSettings, TypingStats},
+    AppResult,
+};
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// A single completed test.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryRecord {
+    pub timestamp: DateTime<Utc>,
+    pub mode: Mode,
+    pub wc: u32,
+    // time limit in seconds when in time mode
+    pub time: u32,
+    pub language: String,
+    pub punctuation: bool,
+    pub capitalization: bool,
+    pub numbers: bool,
+    pub error_mode: ErrorMode,
+    pub wpm: f32,
+    pub accuracy: f32,
+    pub awpm: f32,
+    // seconds spent typing
+    pub duration: f32,
+    pub failed: bool,
+    // keystroke timing was too fast to be typed by hand, kept out of personal bests
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub flagged: bool,
+    // stability of the typing speed in percent, only known for imported tests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consistency: Option<f32>,
+    // seed the prompt was generated from, when it can be generated again from it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    // n-grams practiced when the test was a drill
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drill: Option<Vec<String>>,
+    // words practiced when the test was built from the words missed in an earlier test
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub practice: Option<Vec<String>>,
+    // id of the lesson when the test was part of the curriculum
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lesson: Option<String>,
+    // left out of exports unless asked for, and unknown for imported tests
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keystrokes: Vec<Keystroke>,
+}
+
+impl HistoryRecord {
+    pub fn new(settings: &PromptSettings, stats: &TypingStats, keystrokes: Vec<Keystroke>) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            mode: settings.mode,
+            wc: settings.wc,
+            time: settings.time,
+            language: settings.language.clone(),
+            punctuation: settings.punctuation,
+            capitalization: settings.capitalization,
+            numbers: settings.numbers,
+            error_mode: settings.error_mode,
+            wpm: stats.wpm,
+            accuracy: stats.accuracy,
+            awpm: stats.awpm,
+            duration: stats.duration,
+            failed: stats.failed,
+            flagged: implausible_timing(&keystrokes),
+            consistency: None,
+            seed: None,
+            drill: None,
+            practice: None,
+            lesson: None,
+            keystrokes,
+        }
+    }
+
+    /// A record of a finished test for use in tests, with the given keys typed as
+    /// `(expected, typed, ms)`.
+    #[cfg(test)]
+    pub(crate) fn sample(mode: Mode, wpm: f32, keys: &[(char, char, u64)]) -> Self {
+        let stats = TypingStats {
+            wpm,
+            accuracy: 100.0,
+            awpm: wpm,
+            duration: 10.0,
+            failed: false,
+        };
+        let settings = PromptSettings {
+            mode,
+            ..PromptSettings::new()
+        };
+        let keystrokes = keys
+            .iter()
+            .map(|&(expected, typed, ms)| Keystroke {
+                expected,
+                typed,
+                ms,
+            })
+            .collect();
+        Self::new(&settings, &stats, keystrokes)
+    }
+}
+
+/// Every completed test, stored as one json record per line in the user's data directory.
+#[derive(Debug, Default)]
+pub struct History {
+    pub records: Vec<HistoryRecord>,
+    // where records are appended, `None` keeps the history in memory only
+    path: Option<PathBuf>,
+    // modification time of the file when it was last read or written by us
+    modified: Option<SystemTime>,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl History {
+    /// An empty history that is never written to disk.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Default location of the history file, `<data dir>/type-fast/history.jsonl`.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("type-fast").join(HISTORY_FILE))
+    }
+
+    /// Loads every record from `path`. A missing file is an empty history,
+    /// lines that fail to parse are skipped.
+    pub fn load(path: PathBuf) -> AppResult<Self> {
+        let mut records = vec![];
+        if path.exists() {
+            for (i, line) in fs::read_to_string(&path)?.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str(line) {
+                    Ok(record) => records.push(record),
+                    Err(e) => warn!(target: "history", "skipping line {}: {}", i + 1, e),
+                }
+            }
+        }
+        Ok(Self {
+            records,
+            modified: modified(&path),
+            path: Some(path),
+        })
+    }
+
+    /// Reloads every record when the history file was changed outside of this session,
+    /// e.g. edited by hand or imported into. Returns whether anything was reloaded.
+    pub fn reload_if_changed(&mut self) -> AppResult<bool> {
+        let Some(path) = &self.path else {
+            return Ok(false);
+        };
+        if modified(path) == self.modified {
+            return Ok(false);
+        }
+        *self = Self::load(path.clone())?;
+        Ok(true)
+    }
+
+    /// Adds a record, appending it to the history file.
+    pub fn push(&mut self, record: HistoryRecord) -> AppResult<()> {
+        self.append(vec![record])
+    }
+
+    // appends `records` to the file through a single handle, then keeps them in memory
+    fn append(&mut self, records: Vec<HistoryRecord>) -> AppResult<()> {
+        if let Some(path) = self.path.as_ref().filter(|_| !records.is_empty()) {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            let mut file = BufWriter::new(file);
+            for record in &records {
+                writeln!(file, "{}", serde_json::to_string(record)?)?;
+            }
+            file.flush()?;
+            self.modified = modified(path);
+        }
+        self.records.extend(records);
+        Ok(())
+    }
+
+    /// Adds every record of `records` that is not in the history yet, tests are
+    /// considered the same when they finished at the same time with the same mode and WPM.
+    /// Returns the number added.
+    pub fn import(&mut self, records: Vec<HistoryRecord>) -> AppResult<usize> {
+        let key = |r: &HistoryRecord| (r.timestamp, r.mode, r.wpm.to_bits());
+        let mut seen: HashSet<(DateTime<Utc>, Mode, u32)> = self.records.iter().map(key).collect();
+        let new: Vec<HistoryRecord> = records
+            .into_iter()
+            .filter(|r| seen.insert(key(r)))
+            .collect();
+        let added = new.len();
+        self.append(new)?;
+        Ok(added)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(seconds: i64, mode: Mode, wpm: f32) -> HistoryRecord {
+        let mut record = HistoryRecord::sample(mode, wpm, &[]);
+        record.timestamp = DateTime::from_timestamp(seconds, 0).unwrap();
+        record
+    }
+
+    #[test]
+    fn test_import_skips_tests_already_in_the_history() {
+        let mut history = History::new();
+        history.push(record(1, Mode::Words, 80.0)).unwrap();
+        let added = history
+            .import(vec![
+                record(1, Mode::Words, 80.0),
+                record(1, Mode::Time, 80.0),
+                record(1, Mode::Words, 95.0),
+                record(2, Mode::Words, 80.0),
+                record(2, Mode::Words, 80.0),
+            ])
+            .unwrap();
+        assert_eq!(added, 3);
+        assert_eq!(history.records.len(), 4);
+    }
+}
@@ -0,0 +1,119 @@
+use std::io::Read;
+
+use chrono::DateTime;
+use serde::Deserialize;
+
+use crate::{
+    history::HistoryRecord,
+    typing::{ErrorMode, Mode},
+    AppResult,
+};
+
+// the columns of a monkeytype results export that map onto a history record
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MonkeytypeRow {
+    wpm: f32,
+    raw_wpm: f32,
+    acc: f32,
+    consistency: Option<f32>,
+    mode: String,
+    mode2: String,
+    #[serde(default)]
+    test_duration: f32,
+    #[serde(default)]
+    bailed_out: bool,
+    // milliseconds since the unix epoch
+    timestamp: i64,
+    language: String,
+    punctuation: bool,
+    numbers: bool,
+}
+
+/// Tests read from a monkeytype export, along with how many could not be represented.
+#[derive(Debug, Default)]
+pub struct MonkeytypeImport {
+    pub records: Vec<HistoryRecord>,
+    // quote, zen and custom tests, which have no equivalent here
+    pub unsupported: usize,
+}
+
+impl MonkeytypeRow {
+    fn into_record(self) -> Option<HistoryRecord> {
+        let length: u32 = self.mode2.parse().ok()?;
+        let (mode, wc, time) = match self.mode.as_str() {
+            "words" => (Mode::Words, length, 0),
+            "time" => (Mode::Time, 0, length),
+            _ => return None,
+        };
+        Some(HistoryRecord {
+            timestamp: DateTime::from_timestamp_millis(self.timestamp)?,
+            mode,
+            wc,
+            time,
+            // monkeytype names its word lists like the word banks here, so `english_10k` tests
+            // share personal bests with ours while `english` (200 words) stays apart
+            language: self.language,
+            punctuation: self.punctuation,
+            capitalization: false,
+            numbers: self.numbers,
+            error_mode: ErrorMode::default(),
+            // monkeytype's raw WPM counts every typed character like ours,
+            // its WPM only counts correct ones, which is closest to our accuracy adjusted WPM
+            wpm: self.raw_wpm,
+            accuracy: self.acc,
+            awpm: self.wpm,
+            duration: self.test_duration,
+            failed: self.bailed_out,
+            flagged: false,
+            consistency: self.consistency,
+            seed: None,
+            drill: None,
+            practice: None,
+            lesson: None,
+            keystrokes: vec![],
+        })
+    }
+}
+
+/// Reads the results csv exported from monkeytype's account page.
+pub fn read_monkeytype(reader: impl Read) -> AppResult<MonkeytypeImport> {
+    let mut import = MonkeytypeImport::default();
+    for row in csv::Reader::from_reader(reader).deserialize() {
+        let row: MonkeytypeRow = row?;
+        match row.into_record() {
+            Some(record) => import.records.push(record),
+            None => import.unsupported += 1,
+        }
+    }
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_monkeytype_maps_columns_and_skips_unsupported_modes() {
+        let csv = "\
+_id,isPb,wpm,acc,rawWpm,consistency,charStats,mode,mode2,quoteLength,restartCount,testDuration,afkDuration,incompleteTestSeconds,lazyMode,blindMode,bailedOut,tags,timestamp,language,funbox,difficulty,numbers,punctuation
+a1,true,92.5,97.1,95.2,80.4,250;3;1;0,time,30,-1,0,30,0,0,false,false,false,,1700000000000,english,none,normal,false,true
+a2,false,70,95,74,70,100;2;0;0,quote,medium,1,2,20.5,0,0,false,false,false,,1700000100000,english,none,normal,false,false
+a3,false,81,99,82,85,120;1;0;0,words,25,-1,0,17.2,0,0,false,false,false,,1700000200000,english,none,normal,true,false
+";
+        let import = read_monkeytype(csv.as_bytes()).unwrap();
+        assert_eq!(import.records.len(), 2);
+        assert_eq!(import.unsupported, 1);
+
+        let time = &import.records[0];
+        assert_eq!((time.mode, time.time), (Mode::Time, 30));
+        assert_eq!((time.wpm, time.awpm, time.accuracy), (95.2, 92.5, 97.1));
+        assert!(time.punctuation && !time.numbers);
+        assert_eq!(time.timestamp.timestamp(), 1_700_000_000);
+
+        let words = &import.records[1];
+        assert_eq!((words.mode, words.wc), (Mode::Words, 25));
+        assert_eq!(words.consistency, Some(85.0));
+        assert_eq!(words.language, "english");
+    }
+}
@@ -0,0 +1,34 @@
+//! Typing test engine behind the type-fast terminal app.
+//!
+//! The [`typing::Typing`] engine, the prompt generators in [`typing::LanguagePrompt`], the
+//! statistics in [`stats`] and the [`history::History`] store work without a terminal. The
+//! terminal user interface is built with the default `tui` feature; disable default features
+//! to embed the engine without ratatui or crossterm.
+
+pub mod config;
+pub mod error;
+pub mod export;
+pub mod history;
+pub mod import;
+pub mod stats;
+pub mod theme;
+pub mod typing;
+
+#[cfg(feature = "tui")]
+pub mod app;
+#[cfg(feature = "tui")]
+pub mod event;
+#[cfg(feature = "tui")]
+pub mod handler;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "tui")]
+pub mod ui;
+
+pub use history::{History, HistoryRecord};
+pub use typing::{LanguagePrompt, PromptSettings, Typing, TypingStats};
+
+pub use error::Error;
+
+/// Application result type.
+pub type AppResult<T> = std::result::Result<T, Error>;
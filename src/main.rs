@@ -1,36 +1,48 @@
-use crate::{
-    app::{App, AppResult},
-    cli::Cli,
-    event::{Event, EventHandler},
-    handler::handle_key_events,
+use crate::cli::{Cli, Command, ImportSource};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+};
+use type_fast::{
+    app::{
+        pages::{drill::parse_ngrams, lessons::LessonProgress},
+        App,
+    },
+    config::Config,
+    event::{parse_script, EventHandler},
+    export::{export, ExportFilter, ExportFormat},
+    handler::{handle_event, play},
+    history::History,
+    import::read_monkeytype,
+    stats::summarize,
     tui::Tui,
+    typing::Clock,
+    AppResult, Error,
 };
-use dirs;
-use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
 
 use clap::Parser;
 
 use log::*;
 use tui_logger::*;
 
-pub mod app;
-pub mod cli;
-pub mod config;
-pub mod event;
-pub mod handler;
-pub mod tui;
-pub mod ui;
+mod cli;
 
 #[tokio::main]
 async fn main() -> AppResult<()> {
-    init_logger(LevelFilter::Trace).unwrap();
+    init_logger(LevelFilter::Trace).map_err(|e| Error::Logger(e.to_string()))?;
     set_default_level(LevelFilter::Trace);
 
     let cli = Cli::parse();
-    // TODO load config
-    if cli.config == "" {
-        // load from default, otherwise specified
+    // load from default, otherwise specified, headless runs ignore the user's config so
+    // they give the same results everywhere
+    let mut config = if cli.headless {
+        Config::default()
+    } else {
+        Config::load(&cli.config)?
+    };
+    if let Some(wc) = cli.wc {
+        config.prompt.wc = wc;
     }
 
     trace!(target: "main", "config dir: {:?}", dirs::config_dir());
@@ -38,26 +50,106 @@ async fn main() -> AppResult<()> {
     trace!(target: "main", "skip {}", cli.skip_menu);
 
     // Create an application.
-    let mut app = App::new();
+    // headless runs start from an empty history and lesson progress, and save neither
+    let history = match History::default_path() {
+        Some(path) if !cli.headless => History::load(path)?,
+        _ => History::new(),
+    };
+    let progress = match LessonProgress::default_path() {
+        Some(path) if !cli.headless => LessonProgress::load(path)?,
+        _ => LessonProgress::new(),
+    };
+
+    let mut app = App::with_config(config)
+        .with_history(history)
+        .with_lesson_progress(progress);
+    if let Some(seed) = cli.seed {
+        app = app.with_seed(seed);
+    }
+    match cli.command {
+        Some(Command::Drill { ngram, synthetic }) => {
+            let ngrams = parse_ngrams(&ngram.join(",")).map_err(Error::Invalid)?;
+            app.start_drill(ngrams, synthetic);
+        }
+        // exporting writes to stdout, so it runs without the terminal user interface
+        Some(Command::Export {
+            format,
+            since,
+            mode,
+            keystrokes,
+            output,
+        }) => {
+            let filter = ExportFilter { since, mode };
+            let records = &app.history.records;
+            match output {
+                Some(path) => export(records, File::create(path)?, format, &filter, keystrokes)?,
+                None => export(records, io::stdout().lock(), format, &filter, keystrokes)?,
+            }
+            return Ok(());
+        }
+        Some(Command::Import {
+            source: ImportSource::Monkeytype { file },
+        }) => {
+            let import = read_monkeytype(File::open(file)?)?;
+            let total = import.records.len();
+            let added = app.history.import(import.records)?;
+            println!(
+                "Imported {} tests, skipped {} already in the history and {} quote, zen or custom tests",
+                added,
+                total - added,
+                import.unsupported
+            );
+            return Ok(());
+        }
+        Some(Command::Stats { last, json }) => {
+            let summary = summarize(&app.history.records, last);
+            let mut out = io::stdout().lock();
+            if json {
+                writeln!(out, "{}", serde_json::to_string_pretty(&summary)?)?;
+            } else {
+                write!(out, "{}", summary)?;
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
+    if let Some(script) = cli.input_script {
+        let script = parse_script(&fs::read_to_string(script)?).map_err(Error::Invalid)?;
+        app = app.with_clock(Clock::manual());
+        play(&mut EventHandler::from_events(script), &mut app).await?;
+        let filter = ExportFilter::default();
+        export(
+            &app.history.records,
+            io::stdout().lock(),
+            ExportFormat::Json,
+            &filter,
+            false,
+        )?;
+        return Ok(());
+    }
 
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stdout());
     let terminal = Terminal::new(backend)?;
-    let events = EventHandler::new(250);
+    let events = EventHandler::new(app.tick_rate());
     let mut tui = Tui::new(terminal, events);
     tui.init()?;
 
     // Start the main loop.
     while app.running {
-        // Render the user interface.
-        tui.draw(&mut app)?;
-        // Handle events.
-        match tui.events.next().await? {
-            Event::Tick => app.tick(),
-            Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
-            Event::Mouse(_) => {}
-            Event::Resize(h, w) => app.resize(h, w),
+        // Render the user interface when something changed.
+        if app.dirty {
+            app.dirty = false;
+            tui.draw(&mut app)?;
+        }
+        if app.bell {
+            app.bell = false;
+            tui.bell()?;
         }
+        // Handle events.
+        handle_event(tui.events.next().await?, &mut app)?;
+        tui.events.set_tick_rate(app.tick_rate());
     }
 
     // Exit the user interface.
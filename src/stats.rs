@@ -0,0 +1,514 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use serde::Serialize;
+
+use crate::{
+    history::HistoryRecord,
+    typing::{Keystroke, Mode},
+};
+
+/// Accuracy and timing for a single expected character, aggregated over many tests.
+#[derive(Debug, Default, Clone)]
+pub struct KeyStats {
+    pub hits: u32,
+    pub misses: u32,
+    // milliseconds between the previous keystroke and a correct press of this key
+    latencies: Vec<u64>,
+    // what was typed instead of this key, and how often
+    pub substitutions: HashMap<char, u32>,
+}
+
+impl KeyStats {
+    pub fn presses(&self) -> u32 {
+        self.hits + self.misses
+    }
+
+    /// Fraction of presses that were correct.
+    pub fn hit_rate(&self) -> f32 {
+        if self.presses() == 0 {
+            return 1.0;
+        }
+        self.hits as f32 / self.presses() as f32
+    }
+
+    pub fn error_rate(&self) -> f32 {
+        1.0 - self.hit_rate()
+    }
+
+    /// Average inter-key latency in milliseconds.
+    pub fn average_latency(&self) -> Option<f32> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        Some(self.latencies.iter().sum::<u64>() as f32 / self.latencies.len() as f32)
+    }
+
+    /// 95th percentile inter-key latency in milliseconds.
+    pub fn p95_latency(&self) -> Option<u64> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        let mut sorted = self.latencies.clone();
+        sorted.sort_unstable();
+        let idx = ((sorted.len() as f32 * 0.95).ceil() as usize).clamp(1, sorted.len()) - 1;
+        Some(sorted[idx])
+    }
+
+    /// The most common substitutions, most frequent first.
+    pub fn top_substitutions(&self, n: usize) -> Vec<(char, u32)> {
+        let mut subs: Vec<(char, u32)> = self.substitutions.iter().map(|(c, n)| (*c, *n)).collect();
+        subs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        subs.truncate(n);
+        subs
+    }
+
+    /// Merges the stats of another key into this one, e.g. the shifted and unshifted characters of a key.
+    pub fn merge(&mut self, other: &KeyStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.latencies.extend(&other.latencies);
+        for (c, n) in &other.substitutions {
+            *self.substitutions.entry(*c).or_default() += n;
+        }
+    }
+}
+
+/// Per character stats over the keystroke timelines of `records`.
+pub fn key_stats<'a>(
+    records: impl IntoIterator<Item = &'a HistoryRecord>,
+) -> BTreeMap<char, KeyStats> {
+    aggregate(records, |_, expected| Some(expected))
+}
+
+/// Per bigram stats over the keystroke timelines of `records`, keyed by the
+/// previously expected character and the expected character.
+pub fn bigram_stats<'a>(
+    records: impl IntoIterator<Item = &'a HistoryRecord>,
+) -> BTreeMap<(char, char), KeyStats> {
+    aggregate(records, |previous, expected| {
+        previous.map(|previous| (previous, expected))
+    })
+}
+
+// groups every keystroke under the key returned by `key_of(previous expected, expected)`
+fn aggregate<'a, K: Ord>(
+    records: impl IntoIterator<Item = &'a HistoryRecord>,
+    key_of: impl Fn(Option<char>, char) -> Option<K>,
+) -> BTreeMap<K, KeyStats> {
+    let mut stats: BTreeMap<K, KeyStats> = BTreeMap::new();
+    for record in records {
+        let mut previous: Option<&Keystroke> = None;
+        for keystroke in &record.keystrokes {
+            let Some(k) = key_of(previous.map(|p| p.expected), keystroke.expected) else {
+                previous = Some(keystroke);
+                continue;
+            };
+            let key = stats.entry(k).or_default();
+            if keystroke.typed == keystroke.expected {
+                key.hits += 1;
+                if let Some(previous) = previous {
+                    key.latencies.push(keystroke.ms.saturating_sub(previous.ms));
+                }
+            } else {
+                key.misses += 1;
+                *key.substitutions.entry(keystroke.typed).or_default() += 1;
+            }
+            previous = Some(keystroke);
+        }
+    }
+    stats
+}
+
+// letters and bigrams typed fewer times than this are too noisy to count as weak
+const MIN_PRESSES: u32 = 5;
+
+/// How much each letter and bigram holds the user back, used to bias prompt generation
+/// towards the user's weak spots.
+#[derive(Debug, Default, Clone)]
+pub struct Weakness {
+    letters: HashMap<char, f32>,
+    bigrams: HashMap<(char, char), f32>,
+}
+
+impl Weakness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scores every letter and bigram in `records` by its error rate and by how much
+    /// slower it is than the user's average.
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a HistoryRecord> + Clone) -> Self {
+        let lower = |c: char| c.to_ascii_lowercase();
+
+        let mut letters: HashMap<char, KeyStats> = HashMap::new();
+        for (c, s) in key_stats(records.clone()) {
+            letters.entry(lower(c)).or_default().merge(&s);
+        }
+        let mut bigrams: HashMap<(char, char), KeyStats> = HashMap::new();
+        for ((a, b), s) in bigram_stats(records) {
+            bigrams.entry((lower(a), lower(b))).or_default().merge(&s);
+        }
+
+        let latencies: Vec<f32> = letters
+            .values()
+            .filter_map(|s| s.average_latency())
+            .collect();
+        let baseline = latencies.iter().sum::<f32>() / latencies.len().max(1) as f32;
+        let score = |s: &KeyStats| {
+            if s.presses() < MIN_PRESSES {
+                return None;
+            }
+            let slowness = match s.average_latency() {
+                Some(l) if baseline > 0.0 => (l / baseline - 1.0).max(0.0),
+                _ => 0.0,
+            };
+            Some((s.error_rate() * 5.0).min(1.0) + slowness)
+        };
+
+        Self {
+            letters: letters
+                .iter()
+                .filter(|(c, _)| **c != ' ')
+                .filter_map(|(c, s)| score(s).map(|v| (*c, v)))
+                .collect(),
+            bigrams: bigrams
+                .iter()
+                .filter(|((a, b), _)| *a != ' ' && *b != ' ')
+                .filter_map(|(k, s)| score(s).map(|v| (*k, v)))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters
+            .values()
+            .chain(self.bigrams.values())
+            .all(|v| *v <= 0.0)
+    }
+
+    /// How strongly `word` practices the weak letters and bigrams, 0 when it contains none.
+    pub fn score_word(&self, word: &str) -> f32 {
+        let chars: Vec<char> = word.chars().map(|c| c.to_ascii_lowercase()).collect();
+        let letters: f32 = chars.iter().filter_map(|c| self.letters.get(c)).sum();
+        let bigrams: f32 = chars
+            .windows(2)
+            .filter_map(|w| self.bigrams.get(&(w[0], w[1])))
+            .sum();
+        letters + bigrams
+    }
+}
+
+/// The settings personal bests are tracked for, tests are only compared when all of them match.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PbCategory {
+    pub mode: Mode,
+    // word count in words mode, seconds in time mode
+    pub length: u32,
+    pub language: String,
+    pub punctuation: bool,
+    pub numbers: bool,
+}
+
+impl PbCategory {
+    pub fn of(record: &HistoryRecord) -> Self {
+        Self {
+            mode: record.mode,
+            length: match record.mode {
+                Mode::Words => record.wc,
+                Mode::Time => record.time,
+            },
+            language: record.language.clone(),
+            punctuation: record.punctuation,
+            numbers: record.numbers,
+        }
+    }
+}
+
+impl fmt::Display for PbCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mode {
+            Mode::Words => write!(f, "{} words", self.length)?,
+            Mode::Time => write!(f, "{}s", self.length)?,
+        }
+        write!(f, " {}", self.language)?;
+        if self.punctuation {
+            write!(f, " punctuation")?;
+        }
+        if self.numbers {
+            write!(f, " numbers")?;
+        }
+        Ok(())
+    }
+}
+
+// keys pressed closer together than this are faster than any person types
+const MIN_HUMAN_INTERVAL_MS: u64 = 5;
+// number of keys in a row that must be that fast to count as synthetic input
+const BURST_LENGTH: usize = 5;
+
+/// Whether `keystrokes` contain a burst of keys too fast to be typed by hand, which
+/// means the input was pasted or generated.
+pub fn implausible_timing(keystrokes: &[Keystroke]) -> bool {
+    let mut burst = 1;
+    for pair in keystrokes.windows(2) {
+        if pair[1].ms.saturating_sub(pair[0].ms) < MIN_HUMAN_INTERVAL_MS {
+            burst += 1;
+            if burst >= BURST_LENGTH {
+                return true;
+            }
+        } else {
+            burst = 1;
+        }
+    }
+    false
+}
+
+/// Whether `record` can set a personal best. Drills, lessons and missed word practice use
+/// restricted prompts, failed tests were not finished and flagged tests were not typed by hand,
+/// so none of them count.
+pub fn counts_for_pb(record: &HistoryRecord) -> bool {
+    record.drill.is_none()
+        && record.lesson.is_none()
+        && record.practice.is_none()
+        && !record.failed
+        && !record.flagged
+        // a test ending on its first key has no duration to measure a speed over
+        && record.duration > 0.0
+}
+
+/// The fastest test of every category in `records`, by WPM.
+pub fn personal_bests<'a>(
+    records: impl IntoIterator<Item = &'a HistoryRecord>,
+) -> BTreeMap<PbCategory, &'a HistoryRecord> {
+    let mut bests: BTreeMap<PbCategory, &HistoryRecord> = BTreeMap::new();
+    for record in records.into_iter().filter(|r| counts_for_pb(r)) {
+        let best = bests.entry(PbCategory::of(record)).or_insert(record);
+        if record.wpm > best.wpm {
+            *best = record;
+        }
+    }
+    bests
+}
+
+/// Average and best WPM of the tests of one mode.
+#[derive(Debug, Serialize)]
+pub struct ModeSummary {
+    pub mode: Mode,
+    pub tests: usize,
+    pub average_wpm: f32,
+    pub best_wpm: f32,
+}
+
+/// WPM of the most recent tests, compared with the tests before them.
+#[derive(Debug, Serialize)]
+pub struct Trend {
+    // oldest first
+    pub wpm: Vec<f32>,
+    pub average_wpm: f32,
+    // change in average WPM from the tests before, `None` when there are none
+    pub change: Option<f32>,
+}
+
+/// Totals over the whole history, printed by the `stats` subcommand.
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub tests: usize,
+    // seconds spent typing
+    pub time_typed: f32,
+    pub modes: Vec<ModeSummary>,
+    pub trend: Trend,
+}
+
+fn average(values: &[f32]) -> f32 {
+    values.iter().sum::<f32>() / values.len().max(1) as f32
+}
+
+/// Summarizes `records`, with the trend over the `last` most recent tests. WPM figures only
+/// use tests that count for personal bests, the totals include every test.
+pub fn summarize(records: &[HistoryRecord], last: usize) -> Summary {
+    let mut comparable: Vec<&HistoryRecord> = records.iter().filter(|r| counts_for_pb(r)).collect();
+    comparable.sort_by_key(|r| r.timestamp);
+
+    let modes = Mode::ALL
+        .iter()
+        .map(|mode| {
+            let wpm: Vec<f32> = comparable
+                .iter()
+                .filter(|r| r.mode == *mode)
+                .map(|r| r.wpm)
+                .collect();
+            ModeSummary {
+                mode: *mode,
+                tests: wpm.len(),
+                average_wpm: average(&wpm),
+                best_wpm: wpm.iter().copied().fold(0.0, f32::max),
+            }
+        })
+        .collect();
+
+    let wpm: Vec<f32> = comparable.iter().map(|r| r.wpm).collect();
+    let split = wpm.len().saturating_sub(last);
+    let (before, recent) = wpm.split_at(split);
+    let before = &before[before.len().saturating_sub(last)..];
+    let trend = Trend {
+        wpm: recent.to_vec(),
+        average_wpm: average(recent),
+        change: (!before.is_empty() && !recent.is_empty())
+            .then(|| average(recent) - average(before)),
+    };
+
+    Summary {
+        tests: records.len(),
+        time_typed: records.iter().map(|r| r.duration).sum(),
+        modes,
+        trend,
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.time_typed.round() as u64;
+        writeln!(f, "Tests        {}", self.tests)?;
+        writeln!(
+            f,
+            "Time typed   {}h {:02}m {:02}s",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:<8}{:>7}{:>10}{:>10}",
+            "Mode", "Tests", "Avg WPM", "Best WPM"
+        )?;
+        for mode in &self.modes {
+            writeln!(
+                f,
+                "{:<8}{:>7}{:>10.2}{:>10.2}",
+                mode.mode.to_string(),
+                mode.tests,
+                mode.average_wpm,
+                mode.best_wpm
+            )?;
+        }
+        writeln!(f)?;
+        write!(
+            f,
+            "Last {} tests  {:.2} wpm average",
+            self.trend.wpm.len(),
+            self.trend.average_wpm
+        )?;
+        if let Some(change) = self.trend.change {
+            write!(f, " ({:+.2} on the tests before)", change)?;
+        }
+        writeln!(f)?;
+        let wpm: Vec<String> = self.trend.wpm.iter().map(|w| format!("{:.0}", w)).collect();
+        writeln!(f, "{}", wpm.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::typing::Mode;
+
+    fn record(keys: &[(char, char, u64)]) -> HistoryRecord {
+        HistoryRecord::sample(Mode::Words, 0.0, keys)
+    }
+
+    #[test]
+    fn test_key_stats_counts_hits_misses_and_latency() {
+        let records = [
+            record(&[('a', 'a', 0), ('b', 'v', 100), ('b', 'b', 300)]),
+            record(&[('a', 'a', 0), ('b', 'b', 100), ('a', 's', 150)]),
+        ];
+        let stats = key_stats(&records);
+
+        let a = &stats[&'a'];
+        assert_eq!((a.hits, a.misses), (2, 1));
+        assert_eq!(a.average_latency(), None);
+        assert_eq!(a.top_substitutions(3), vec![('s', 1)]);
+
+        let b = &stats[&'b'];
+        assert_eq!(b.hit_rate(), 2.0 / 3.0);
+        assert_eq!(b.average_latency(), Some(150.0));
+        assert_eq!(b.p95_latency(), Some(200));
+
+        let ab = &bigram_stats(&records)[&('a', 'b')];
+        assert_eq!((ab.hits, ab.misses), (1, 1));
+    }
+
+    #[test]
+    fn test_weakness_scores_words_with_error_prone_letters() {
+        // 'x' is missed half the time, every other letter is always correct
+        let mut keys = vec![];
+        for i in 0..10 {
+            keys.push(('a', 'a', i * 200));
+            keys.push(('x', if i % 2 == 0 { 'x' } else { 'c' }, i * 200 + 100));
+        }
+        let weakness = Weakness::from_records(&[record(&keys)]);
+
+        assert!(!weakness.is_empty());
+        assert!(weakness.score_word("box") > weakness.score_word("bat"));
+        assert_eq!(weakness.score_word("the"), 0.0);
+    }
+
+    #[test]
+    fn test_bursts_of_instant_keys_are_implausible() {
+        let typed = record(&[
+            ('a', 'a', 0),
+            ('b', 'b', 80),
+            ('c', 'c', 83),
+            ('d', 'd', 150),
+        ]);
+        assert!(!implausible_timing(&typed.keystrokes));
+
+        let pasted: Vec<(char, char, u64)> = "pasted".chars().map(|c| (c, c, 100)).collect();
+        let pasted = record(&pasted);
+        assert!(pasted.flagged);
+        assert!(!counts_for_pb(&pasted));
+    }
+
+    #[test]
+    fn test_personal_bests_are_kept_per_category() {
+        let mut slow = record(&[]);
+        slow.wpm = 60.0;
+        let mut fast = slow.clone();
+        fast.wpm = 80.0;
+        let mut punctuation = slow.clone();
+        punctuation.punctuation = true;
+        let mut drill = slow.clone();
+        drill.wpm = 120.0;
+        drill.drill = Some(vec!["th".to_string()]);
+
+        let records = [slow, fast, punctuation, drill];
+        let bests = personal_bests(&records);
+        assert_eq!(bests.len(), 2);
+        assert_eq!(bests[&PbCategory::of(&records[0])].wpm, 80.0);
+        assert_eq!(bests[&PbCategory::of(&records[2])].wpm, 60.0);
+    }
+
+    #[test]
+    fn test_summary_trend_compares_recent_tests_with_the_ones_before() {
+        let records: Vec<HistoryRecord> = [50.0, 60.0, 70.0, 80.0]
+            .iter()
+            .map(|wpm| {
+                let mut r = record(&[]);
+                r.wpm = *wpm;
+                r.duration = 30.0;
+                r
+            })
+            .collect();
+        let summary = summarize(&records, 2);
+
+        assert_eq!(summary.tests, 4);
+        assert_eq!(summary.time_typed, 120.0);
+        assert_eq!(summary.modes[0].best_wpm, 80.0);
+        assert_eq!(summary.trend.wpm, vec![70.0, 80.0]);
+        assert_eq!(summary.trend.change, Some(20.0));
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+
+#[cfg(feature = "tui")]
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+/// Color themes selectable from the settings page.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    Ocean,
+}
+
+/// Colors used when rendering a page.
+#[cfg(feature = "tui")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Palette {
+    pub bg: Color,
+    pub fg: Color,
+    pub correct: Color,
+    pub incorrect: Color,
+    pub accent: Color,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::Ocean];
+
+    #[cfg(feature = "tui")]
+    pub fn palette(&self) -> Palette {
+        match self {
+            Theme::Dark => Palette {
+                bg: Color::Black,
+                fg: Color::White,
+                correct: Color::DarkGray,
+                incorrect: Color::Red,
+                accent: Color::Yellow,
+            },
+            Theme::Light => Palette {
+                bg: Color::White,
+                fg: Color::Black,
+                correct: Color::Gray,
+                incorrect: Color::Red,
+                accent: Color::Blue,
+            },
+            Theme::Ocean => Palette {
+                bg: Color::Rgb(15, 30, 45),
+                fg: Color::Rgb(200, 220, 235),
+                correct: Color::Rgb(80, 110, 130),
+                incorrect: Color::LightRed,
+                accent: Color::Cyan,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Theme::Dark => write!(f, "dark"),
+            Theme::Light => write!(f, "light"),
+            Theme::Ocean => write!(f, "ocean"),
+        }
+    }
+}
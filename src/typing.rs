@@ -0,0 +1,1325 @@
+use rand::{
+    distributions::{Uniform, WeightedIndex},
+    prelude::Distribution,
+    rngs::StdRng,
+    Rng, SeedableRng,
+};
+
+use rust_embed::Embed;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    ops::Range,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use log::error;
+
+use crate::{stats::Weakness, theme::Theme, AppResult, Error};
+
+static LANGUAGES: OnceLock<Vec<LanguagePrompt>> = OnceLock::new();
+
+pub const DEFAULT_LANGUAGE: &str = "english_10k";
+
+// punctuation that may be appended to a word when punctuation is enabled
+const PUNCTUATION: [char; 6] = [',', '.', '?', '!', ';', ':'];
+
+#[derive(Embed)]
+#[folder = "src/language/"]
+struct EnglishEmbeded;
+
+/// How a letter of the prompt has been typed so far.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LetterState {
+    Unpressed,
+    Incorrect,
+    Correct,
+}
+
+/// A letter of the prompt along with its state, spaces are written as `•`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TypingLetter {
+    pub state: LetterState,
+    pub value: char,
+}
+
+impl TypingLetter {
+    pub fn new(c: char, state: LetterState) -> Self {
+        Self { state, value: c }
+    }
+}
+
+/// A single key press during a test.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Keystroke {
+    // character the prompt expected, spaces are stored as ' '
+    pub expected: char,
+    pub typed: char,
+    // milliseconds since the first keystroke of the test
+    pub ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct LanguagePrompt {
+    name: String,
+    #[serde(rename = "noLazyMode")]
+    no_lazy_mode: bool,
+    #[serde(rename = "orderedByFrequency")]
+    ordered_by_frequency: bool,
+    words: Vec<String>,
+}
+
+impl LanguagePrompt {
+    // every json file in the language folder is a word bank, keyed by its name.
+    // Files that cannot be read, or have no words, are left out
+    fn languages() -> &'static Vec<LanguagePrompt> {
+        LANGUAGES.get_or_init(|| {
+            EnglishEmbeded::iter()
+                .filter_map(|name| {
+                    let file = EnglishEmbeded::get(&name)?;
+                    match serde_json::from_slice::<LanguagePrompt>(&file.data) {
+                        Ok(language) if !language.words.is_empty() => Some(language),
+                        Ok(_) => {
+                            error!(target: "language", "word bank {} has no words", name);
+                            None
+                        }
+                        Err(e) => {
+                            error!(target: "language", "failed to read word bank {}: {}", name, e);
+                            None
+                        }
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Names of all the embedded word banks.
+    pub fn available() -> Vec<&'static str> {
+        Self::languages().iter().map(|l| l.name.as_str()).collect()
+    }
+
+    /// Word bank with the given name, if it exists.
+    pub fn get(name: &str) -> Option<&'static LanguagePrompt> {
+        Self::languages().iter().find(|l| l.name == name)
+    }
+
+    /// Word bank selected in `settings`, falling back to the default language.
+    pub fn for_settings(settings: &PromptSettings) -> AppResult<&'static LanguagePrompt> {
+        Self::get(&settings.language)
+            .or_else(|| Self::get(DEFAULT_LANGUAGE))
+            .ok_or_else(|| Error::Language(settings.language.clone()))
+    }
+
+    // this object will be created via serde
+    // once the words are available the caller will
+    // want to generate a random subset to call the prompt
+    // we will convert the strings into a sequence of characters
+    //
+    // when adaptive practice is on and a weakness is given, a share of the words
+    // is drawn weighted towards the user's weak letters and bigrams
+    //
+    // the same seed, settings and weakness always generate the same prompt
+    pub fn generate(
+        &self,
+        settings: &PromptSettings,
+        weakness: Option<&Weakness>,
+        seed: u64,
+    ) -> Vec<char> {
+        if self.words.is_empty() {
+            return vec![];
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let uni = Uniform::from(0..self.words.len());
+        let weighted = weakness
+            .filter(|w| settings.adaptive && !w.is_empty())
+            .and_then(|w| WeightedIndex::new(self.words.iter().map(|x| w.score_word(x))).ok());
+        let ratio = settings.adaptive_ratio as f64 / 100.0;
+        let mut chars = vec![];
+        let mut sentence_start = true;
+
+        for _ in 0..settings.word_count() {
+            let mut word = if settings.numbers && rng.gen_bool(0.1) {
+                rng.gen_range(0..10_000).to_string()
+            } else {
+                let idx = match &weighted {
+                    Some(weighted) if rng.gen_bool(ratio) => weighted.sample(&mut rng),
+                    _ => uni.sample(&mut rng),
+                };
+                self.words[idx].clone()
+            };
+
+            // without punctuation there are no sentences, so capitalize at random instead
+            let capitalize = if settings.punctuation {
+                sentence_start
+            } else {
+                rng.gen_bool(0.2)
+            };
+            if settings.capitalization && capitalize {
+                word = capitalize_word(&word);
+            }
+
+            sentence_start = false;
+            if settings.punctuation && rng.gen_bool(0.15) {
+                let p = PUNCTUATION[rng.gen_range(0..PUNCTUATION.len())];
+                sentence_start = matches!(p, '.' | '?' | '!');
+                word.push(p);
+            }
+
+            chars.extend(word.chars());
+            chars.push('•');
+        }
+
+        if chars.last() == Some(&'•') {
+            chars.pop();
+        }
+        chars
+    }
+
+    /// Generates a drill prompt practicing `ngrams`, using real words that contain one of them.
+    /// Synthetic drills, or n-grams no word contains, repeat the n-gram itself instead.
+    /// Without n-grams the prompt is empty.
+    pub fn generate_drill(
+        &self,
+        ngrams: &[String],
+        synthetic: bool,
+        word_count: u32,
+        seed: u64,
+    ) -> Vec<char> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let ngrams: Vec<String> = ngrams.iter().map(|n| n.to_lowercase()).collect();
+        let words: Vec<&String> = if synthetic {
+            vec![]
+        } else {
+            self.words
+                .iter()
+                .filter(|w| {
+                    let w = w.to_lowercase();
+                    ngrams.iter().any(|n| w.contains(n.as_str()))
+                })
+                .collect()
+        };
+
+        if ngrams.is_empty() {
+            return vec![];
+        }
+        let mut prompt = vec![];
+        for _ in 0..word_count {
+            let word = if words.is_empty() {
+                ngrams[rng.gen_range(0..ngrams.len())].repeat(rng.gen_range(1..=3))
+            } else {
+                words[rng.gen_range(0..words.len())].clone()
+            };
+            prompt.push(word);
+        }
+        prompt.join("•").chars().collect()
+    }
+
+    /// Generates a prompt of `word_count` words picked from `words`, used to practice
+    /// the words missed in a test. Without words the prompt is empty.
+    pub fn generate_practice(words: &[String], word_count: u32, seed: u64) -> Vec<char> {
+        if words.is_empty() {
+            return vec![];
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let prompt: Vec<&str> = (0..word_count)
+            .map(|_| words[rng.gen_range(0..words.len())].as_str())
+            .collect();
+        prompt.join("•").chars().collect()
+    }
+
+    /// Generates a lesson prompt using only characters from `charset`. Real words made of
+    /// those characters are mixed with groups of the `focus` keys the lesson introduces.
+    /// The prompt is empty when neither words nor focus keys are available.
+    pub fn generate_lesson(
+        &self,
+        charset: &str,
+        focus: &str,
+        word_count: u32,
+        seed: u64,
+    ) -> Vec<char> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let words: Vec<&String> = self
+            .words
+            .iter()
+            .filter(|w| w.chars().all(|c| charset.contains(c)))
+            .collect();
+        let focus: Vec<char> = focus.chars().collect();
+        let synthetic = match (words.is_empty(), focus.is_empty()) {
+            (true, true) => return vec![],
+            (true, false) => 1.0,
+            (false, true) => 0.0,
+            (false, false) => 0.3,
+        };
+
+        let mut prompt = vec![];
+        for _ in 0..word_count {
+            let word = if rng.gen_bool(synthetic) {
+                (0..rng.gen_range(2..=5))
+                    .map(|_| focus[rng.gen_range(0..focus.len())])
+                    .collect()
+            } else {
+                words[rng.gen_range(0..words.len())].clone()
+            };
+            prompt.push(word);
+        }
+        prompt.join("•").chars().collect()
+    }
+}
+
+fn capitalize_word(word: &str) -> String {
+    let mut c = word.chars();
+    match c.next() {
+        Some(first) => first.to_uppercase().chain(c).collect(),
+        None => String::new(),
+    }
+}
+
+/// Whether a test ends after a number of words or a number of seconds.
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "tui", derive(clap::ValueEnum))]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    #[default]
+    Words,
+    Time,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 2] = [Mode::Words, Mode::Time];
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Words => write!(f, "words"),
+            Mode::Time => write!(f, "time"),
+        }
+    }
+}
+
+/// How the current position in the prompt is drawn.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Caret {
+    #[default]
+    Underline,
+    Block,
+    Off,
+}
+
+impl Caret {
+    pub const ALL: [Caret; 3] = [Caret::Underline, Caret::Block, Caret::Off];
+}
+
+impl fmt::Display for Caret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Caret::Underline => write!(f, "underline"),
+            Caret::Block => write!(f, "block"),
+            Caret::Off => write!(f, "off"),
+        }
+    }
+}
+
+/// What happens when a typed character does not match the prompt.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorMode {
+    /// A wrong character blocks progress until the correct one is typed.
+    #[default]
+    Letter,
+    /// Errors move on within a word, but the space after it is refused until the word is correct.
+    Word,
+    /// Errors move on and are recorded.
+    Free,
+    /// The test fails on the first error.
+    SuddenDeath,
+}
+
+impl ErrorMode {
+    pub const ALL: [ErrorMode; 4] = [
+        ErrorMode::Letter,
+        ErrorMode::Word,
+        ErrorMode::Free,
+        ErrorMode::SuddenDeath,
+    ];
+
+    /// Whether mistakes can be removed with backspace.
+    pub fn allows_backspace(&self) -> bool {
+        matches!(self, ErrorMode::Word | ErrorMode::Free)
+    }
+}
+
+impl fmt::Display for ErrorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorMode::Letter => write!(f, "letter"),
+            ErrorMode::Word => write!(f, "word"),
+            ErrorMode::Free => write!(f, "free"),
+            ErrorMode::SuddenDeath => write!(f, "sudden death"),
+        }
+    }
+}
+
+/// What happens to a test when no key is pressed for the idle timeout.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleAction {
+    /// Pause the test, leaving the idle time out of the clock.
+    #[default]
+    Pause,
+    /// Start the prompt over, so the test never reaches the history.
+    Discard,
+}
+
+impl IdleAction {
+    pub const ALL: [IdleAction; 2] = [IdleAction::Pause, IdleAction::Discard];
+}
+
+impl fmt::Display for IdleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdleAction::Pause => write!(f, "pause"),
+            IdleAction::Discard => write!(f, "discard"),
+        }
+    }
+}
+
+// keystrokes typed before the minimum accuracy is enforced, so one early mistake does not end the test
+const MIN_ACCURACY_GRACE: usize = 10;
+
+pub const MIN_WC: u32 = 1;
+pub const MAX_WC: u32 = 10_000;
+pub const DEFAULT_WC_PRESETS: [u32; 4] = [25, 50, 75, 100];
+// available time limits in seconds for time mode
+pub const TIMES: [u32; 4] = [15, 30, 60, 120];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PromptSettings {
+    pub wc: u32,
+    // word counts cycled through by the word count hotkey
+    pub wc_presets: Vec<u32>,
+    pub mode: Mode,
+    // time limit in seconds when in time mode
+    pub time: u32,
+    pub language: String,
+    pub theme: Theme,
+    pub caret: Caret,
+    pub punctuation: bool,
+    pub capitalization: bool,
+    pub numbers: bool,
+    pub zen: bool,
+    // bias word selection towards weak letters and bigrams from the history
+    pub adaptive: bool,
+    // percentage of words chosen by weakness when adaptive, the rest stay uniform so prompts read naturally
+    pub adaptive_ratio: u8,
+    pub error_mode: ErrorMode,
+    // the test fails when accuracy drops below this percentage, 0 disables it
+    pub min_accuracy: u8,
+    pub sounds: bool,
+    // seconds without a key press before a started test is paused or discarded, 0 disables it
+    pub idle_timeout: u32,
+    pub idle_action: IdleAction,
+}
+
+impl Default for PromptSettings {
+    fn default() -> Self {
+        Self {
+            wc: 25,
+            wc_presets: DEFAULT_WC_PRESETS.to_vec(),
+            mode: Mode::default(),
+            time: 30,
+            language: DEFAULT_LANGUAGE.to_string(),
+            theme: Theme::default(),
+            caret: Caret::default(),
+            punctuation: false,
+            capitalization: false,
+            numbers: false,
+            zen: false,
+            adaptive: false,
+            adaptive_ratio: 30,
+            error_mode: ErrorMode::default(),
+            min_accuracy: 0,
+            sounds: false,
+            idle_timeout: 30,
+            idle_action: IdleAction::default(),
+        }
+    }
+}
+
+impl PromptSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn toggle_zen(&mut self) {
+        self.zen = !self.zen;
+    }
+
+    pub fn toggle_punctuation(&mut self) {
+        self.punctuation = !self.punctuation;
+    }
+
+    pub fn toggle_capitalization(&mut self) {
+        self.capitalization = !self.capitalization;
+    }
+
+    pub fn toggle_numbers(&mut self) {
+        self.numbers = !self.numbers;
+    }
+
+    pub fn toggle_adaptive(&mut self) {
+        self.adaptive = !self.adaptive;
+    }
+
+    pub fn toggle_sounds(&mut self) {
+        self.sounds = !self.sounds;
+    }
+
+    /// Moves to the next larger preset word count, wrapping around to the smallest.
+    pub fn next_wc(&mut self) {
+        self.wc = self
+            .wc_presets
+            .iter()
+            .find(|&&p| p > self.wc)
+            .or(self.wc_presets.first())
+            .copied()
+            .unwrap_or(self.wc);
+    }
+
+    /// Moves to the next smaller preset word count, wrapping around to the largest.
+    pub fn previous_wc(&mut self) {
+        self.wc = self
+            .wc_presets
+            .iter()
+            .rev()
+            .find(|&&p| p < self.wc)
+            .or(self.wc_presets.last())
+            .copied()
+            .unwrap_or(self.wc);
+    }
+
+    /// Sets the word count, rejecting values outside of [`MIN_WC`]..=[`MAX_WC`].
+    pub fn set_wc(&mut self, wc: u32) -> Result<(), String> {
+        if !(MIN_WC..=MAX_WC).contains(&wc) {
+            return Err(format!(
+                "word count must be between {} and {}",
+                MIN_WC, MAX_WC
+            ));
+        }
+        self.wc = wc;
+        Ok(())
+    }
+
+    /// Sets the word count presets, each must be a valid word count.
+    pub fn set_wc_presets(&mut self, mut presets: Vec<u32>) -> Result<(), String> {
+        if presets.is_empty() {
+            return Err("at least one preset is required".to_string());
+        }
+        if let Some(p) = presets.iter().find(|p| !(MIN_WC..=MAX_WC).contains(p)) {
+            return Err(format!(
+                "preset {} must be between {} and {}",
+                p, MIN_WC, MAX_WC
+            ));
+        }
+        presets.sort_unstable();
+        presets.dedup();
+        self.wc_presets = presets;
+        Ok(())
+    }
+
+    /// Sets the minimum accuracy percentage, 0 turns it off.
+    pub fn set_min_accuracy(&mut self, min_accuracy: u32) -> Result<(), String> {
+        if min_accuracy > 100 {
+            return Err("minimum accuracy must be between 0 and 100".to_string());
+        }
+        self.min_accuracy = min_accuracy as u8;
+        Ok(())
+    }
+
+    /// Sets the percentage of adaptive words in a prompt.
+    pub fn set_adaptive_ratio(&mut self, ratio: u32) -> Result<(), String> {
+        if ratio > 100 {
+            return Err("adaptive mix must be between 0 and 100".to_string());
+        }
+        self.adaptive_ratio = ratio as u8;
+        Ok(())
+    }
+
+    /// Number of words to generate for a prompt. Time mode generates enough
+    /// words that the prompt will not run out before the timer does.
+    pub fn word_count(&self) -> u32 {
+        match self.mode {
+            Mode::Words => self.wc,
+            Mode::Time => self.time * 4,
+        }
+    }
+
+    /// Replaces any out of range values, such as those from a hand edited config file.
+    pub fn validate(&mut self) {
+        let defaults = Self::default();
+        if !(MIN_WC..=MAX_WC).contains(&self.wc) {
+            self.wc = defaults.wc;
+        }
+        if self.set_wc_presets(self.wc_presets.clone()).is_err() {
+            self.wc_presets = defaults.wc_presets;
+        }
+        if !TIMES.contains(&self.time) {
+            self.time = defaults.time;
+        }
+        if self.min_accuracy > 100 {
+            self.min_accuracy = defaults.min_accuracy;
+        }
+        if self.adaptive_ratio > 100 {
+            self.adaptive_ratio = defaults.adaptive_ratio;
+        }
+        if LanguagePrompt::get(&self.language).is_none() {
+            self.language = defaults.language;
+        }
+    }
+}
+
+/// Where a test reads the time from. Scripted runs use a manual clock that only moves when
+/// the script says so, so the same key presses always give the same results.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Clock {
+    #[default]
+    System,
+    Manual {
+        start: Instant,
+        elapsed: Duration,
+    },
+}
+
+impl Clock {
+    /// A manual clock, stopped until [`Clock::advance`] is called.
+    pub fn manual() -> Self {
+        Clock::Manual {
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        match self {
+            Clock::System => Instant::now(),
+            Clock::Manual { start, elapsed } => *start + *elapsed,
+        }
+    }
+
+    /// Wall clock time, a manual clock counts from the Unix epoch so that scripted runs
+    /// record the same timestamps every time.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Manual { elapsed, .. } => DateTime::UNIX_EPOCH + *elapsed,
+        }
+    }
+
+    /// Moves a manual clock forward, the system clock cannot be moved.
+    pub fn advance(&mut self, by: Duration) {
+        if let Clock::Manual { elapsed, .. } = self {
+            *elapsed += by;
+        }
+    }
+
+    // time passed since `earlier`
+    fn since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+#[derive(Debug)]
+pub struct Typing {
+    // position in the phrase
+    position: usize,
+    // current sequence of typed characters by the user
+    pub typing: Vec<char>,
+    // phrase the user is attempting to type
+    phrase: Vec<char>,
+    // state of each typed character position, used for rendering logic
+    state: Vec<TypingLetter>,
+    // identifies this prompt and attempt, so renderers know when to start over
+    revision: u64,
+    // positions whose state changed during this attempt, oldest first, so renderers only
+    // restyle those letters
+    changed: Vec<usize>,
+
+    // settings the prompt was generated with, fixed until the test ends so the result is
+    // recorded with the settings that were typed
+    pub settings: PromptSettings,
+
+    // timeline of every keystroke, used for per key statistics
+    keystrokes: Vec<Keystroke>,
+    // number of keystrokes that did not match the prompt
+    errors: usize,
+    // whether the most recent keystroke was an error, used for sounds
+    last_error: bool,
+    // positions of the phrase that were mistyped at least once, kept after backspacing
+    missed: Vec<bool>,
+    // set when the test ended early because of the error mode or minimum accuracy
+    failed: bool,
+
+    clock: Clock,
+    start_time: Instant,
+    duration: Duration,
+    // time of the last key press, used to detect an idle user
+    last_input: Instant,
+    // when the test was paused, the clock does not run while paused
+    paused_at: Option<Instant>,
+}
+
+// revisions are unique across prompts, so a new prompt is never mistaken for the last one
+fn next_revision() -> u64 {
+    static REVISIONS: AtomicU64 = AtomicU64::new(0);
+    REVISIONS.fetch_add(1, Ordering::Relaxed)
+}
+
+impl Typing {
+    /// Creates a prompt of random words from the language selected in `settings`.
+    pub fn new(settings: PromptSettings) -> AppResult<Self> {
+        let phrase =
+            LanguagePrompt::for_settings(&settings)?.generate(&settings, None, rand::random());
+        Ok(Self::with_phrase(phrase, settings))
+    }
+
+    /// Creates a prompt for a given phrase, with spaces written as `•`.
+    pub fn with_phrase(phrase: Vec<char>, settings: PromptSettings) -> Self {
+        let state: Vec<TypingLetter> = Self::setup_state(&phrase);
+        let phrase_len = phrase.len();
+
+        Self {
+            position: 0,
+            typing: vec![],
+            phrase,
+            state,
+            revision: next_revision(),
+            changed: vec![],
+            settings,
+            keystrokes: vec![],
+            errors: 0,
+            last_error: false,
+            missed: vec![false; phrase_len],
+            failed: false,
+            clock: Clock::System,
+            start_time: Instant::now(),
+            duration: Duration::default(),
+            last_input: Instant::now(),
+            paused_at: None,
+        }
+    }
+
+    // reset fields with current prompt
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.typing = vec![];
+        self.state = Self::setup_state(&self.phrase);
+        self.revision = next_revision();
+        self.changed.clear();
+        self.keystrokes = vec![];
+        self.errors = 0;
+        self.last_error = false;
+        self.missed = vec![false; self.phrase.len()];
+        self.failed = false;
+        self.start_time = self.clock.now();
+        self.paused_at = None;
+    }
+
+    pub fn clock(&self) -> Clock {
+        self.clock
+    }
+
+    /// Reads the time from `clock` from now on.
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+        self.start_time = clock.now();
+        self.last_input = clock.now();
+    }
+
+    /// Moves a manual clock forward.
+    pub fn advance_clock(&mut self, by: Duration) {
+        self.clock.advance(by);
+    }
+
+    /// Stops the test clock until [`Typing::resume`] is called.
+    pub fn pause(&mut self) {
+        self.paused_at.get_or_insert(self.clock.now());
+    }
+
+    /// Pauses the test as of the last key press, so the time spent idle is not counted.
+    pub fn pause_idle(&mut self) {
+        self.paused_at.get_or_insert(self.last_input);
+    }
+
+    /// Whether the first key of the test has been pressed.
+    pub fn started(&self) -> bool {
+        !self.typing.is_empty()
+    }
+
+    /// Time spent on the test so far, leaving out pauses.
+    pub fn elapsed(&self) -> Duration {
+        if !self.started() {
+            return Duration::ZERO;
+        }
+        let now = self.paused_at.unwrap_or(self.clock.now());
+        now.saturating_duration_since(self.start_time)
+    }
+
+    /// Words per minute typed so far.
+    pub fn live_wpm(&self) -> f32 {
+        wpm(self.typing.len(), self.elapsed())
+    }
+
+    /// How long since the last key press, `None` before the test has started.
+    pub fn idle_for(&self) -> Option<Duration> {
+        if self.typing.is_empty() || self.paused_at.is_some() {
+            return None;
+        }
+        Some(self.clock.since(self.last_input))
+    }
+
+    /// Starts the test clock again, leaving out the time spent paused.
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.start_time += self.clock.since(paused_at);
+        }
+    }
+
+    //
+    fn setup_state(phrase: &[char]) -> Vec<TypingLetter> {
+        phrase
+            .iter()
+            .map(|x| TypingLetter::new(*x, LetterState::Unpressed))
+            .collect()
+    }
+
+    // take in the current user input
+    pub fn input(&mut self, c: char) -> bool {
+        // overwrite start_time if typing is empty
+        if self.typing.is_empty() {
+            self.start_time = self.clock.now();
+        }
+        self.last_input = self.clock.now();
+        self.typing.push(c);
+
+        if let Some(&expected) = self.phrase.get(self.position) {
+            let space = |c: char| if c == '•' { ' ' } else { c };
+            self.keystrokes.push(Keystroke {
+                expected: space(expected),
+                typed: space(c),
+                ms: self.clock.since(self.start_time).as_millis() as u64,
+            });
+        }
+        self.character_matching(c);
+        self.check_min_accuracy();
+
+        let error_mode = self.settings.error_mode;
+        // in word mode the last word has no space after it, so it must be correct to finish
+        let finished = self.position >= self.phrase.len()
+            && (error_mode != ErrorMode::Word || self.word_correct());
+        if finished || self.failed {
+            self.duration = self.clock.since(self.start_time);
+            return true;
+        }
+        false
+    }
+
+    pub fn character_matching(&mut self, c: char) {
+        let error_mode = self.settings.error_mode;
+
+        // only reachable in word mode, when the last word still has errors
+        if self.position >= self.phrase.len() {
+            self.errors += 1;
+            self.last_error = true;
+            return;
+        }
+
+        let current_char = self.phrase[self.position];
+        // the space after a word is refused until the word is fully correct
+        let blocked = error_mode == ErrorMode::Word && current_char == '•' && !self.word_correct();
+        self.last_error = c != current_char || blocked;
+
+        self.changed.push(self.position);
+        if !self.last_error {
+            if self.state[self.position].state != LetterState::Incorrect {
+                self.state[self.position] = TypingLetter::new(c, LetterState::Correct);
+            }
+
+            self.position += 1;
+        } else {
+            self.errors += 1;
+            self.missed[self.position] = true;
+            self.state[self.position] = TypingLetter::new(current_char, LetterState::Incorrect);
+            match error_mode {
+                ErrorMode::Letter => {}
+                ErrorMode::Word if current_char == '•' => {}
+                ErrorMode::Word | ErrorMode::Free => self.position += 1,
+                ErrorMode::SuddenDeath => self.failed = true,
+            }
+        }
+    }
+
+    /// Removes the last typed character of the current word, when the error mode allows it.
+    pub fn backspace(&mut self) {
+        self.last_input = self.clock.now();
+        if !self.settings.error_mode.allows_backspace() {
+            return;
+        }
+        // never move back past the start of the current word
+        if self.position == 0 || self.phrase[self.position - 1] == '•' {
+            return;
+        }
+        // a refused space is cleared along with the letter before it
+        if self.position < self.phrase.len() {
+            self.state[self.position].state = LetterState::Unpressed;
+            self.changed.push(self.position);
+        }
+        self.position -= 1;
+        self.state[self.position].state = LetterState::Unpressed;
+        self.changed.push(self.position);
+    }
+
+    // whether every letter typed so far in the current word is correct
+    fn word_correct(&self) -> bool {
+        self.state[..self.position]
+            .iter()
+            .rev()
+            .take_while(|l| l.value != '•')
+            .all(|l| l.state == LetterState::Correct)
+    }
+
+    fn check_min_accuracy(&mut self) {
+        let min_accuracy = self.settings.min_accuracy;
+        if min_accuracy == 0 || self.typing.len() < MIN_ACCURACY_GRACE {
+            return;
+        }
+        if self.accuracy() * 100_f32 < min_accuracy as f32 {
+            self.failed = true;
+        }
+    }
+
+    // fraction of keystrokes that matched the prompt
+    fn accuracy(&self) -> f32 {
+        (self.typing.len() - self.errors) as f32 / self.typing.len() as f32
+    }
+
+    /// Every keystroke of the test so far.
+    pub fn keystrokes(&self) -> &[Keystroke] {
+        &self.keystrokes
+    }
+
+    /// Words of the prompt that were mistyped, each listed once. A mistyped space
+    /// counts towards the word before it.
+    pub fn missed_words(&self) -> Vec<String> {
+        let mut words: Vec<String> = vec![];
+        for range in self.words() {
+            let end = (range.end + 1).min(self.phrase.len());
+            let word: String = self.phrase[range.clone()].iter().collect();
+            if self.missed[range.start..end].contains(&true) && !words.contains(&word) {
+                words.push(word);
+            }
+        }
+        words
+    }
+
+    /// Every letter of the prompt with how it has been typed.
+    pub fn letters(&self) -> &[TypingLetter] {
+        &self.state
+    }
+
+    /// Identifies the prompt and attempt, a new prompt or a reset gets a new revision.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Positions of the letters whose state changed since the revision began, oldest first.
+    pub fn changed_letters(&self) -> &[usize] {
+        &self.changed
+    }
+
+    /// Index of the next letter to type, `None` once the whole prompt has been typed.
+    pub fn caret(&self) -> Option<usize> {
+        (self.position < self.phrase.len()).then_some(self.position)
+    }
+
+    /// Ranges of letters making up each word of the prompt, without the spaces between them.
+    pub fn words(&self) -> Vec<Range<usize>> {
+        let mut words = vec![];
+        let mut start = 0;
+        for (i, c) in self.phrase.iter().enumerate() {
+            if *c == '•' {
+                words.push(start..i);
+                start = i + 1;
+            }
+        }
+        if start < self.phrase.len() {
+            words.push(start..self.phrase.len());
+        }
+        words
+    }
+
+    /// Whether the test ended early because of the error mode or minimum accuracy.
+    pub fn failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Whether the most recent keystroke did not match the prompt.
+    pub fn last_was_error(&self) -> bool {
+        self.last_error
+    }
+
+    /// In time mode, whether the time limit has been reached. Sets the final duration when it has.
+    pub fn time_up(&mut self) -> bool {
+        let settings = &self.settings;
+        if settings.mode != Mode::Time || self.typing.is_empty() {
+            return false;
+        }
+        let limit = Duration::from_secs(settings.time as u64);
+        if self.clock.since(self.start_time) >= limit {
+            self.duration = limit;
+            return true;
+        }
+        false
+    }
+
+    pub fn calculate_statistics(&self) -> TypingStats {
+        let wpm = wpm(self.typing.len(), self.duration);
+        let acc = self.accuracy();
+        TypingStats {
+            wpm,
+            accuracy: acc * 100_f32,
+            awpm: wpm * acc,
+            duration: self.duration.as_secs_f32(),
+            failed: self.failed,
+        }
+    }
+}
+
+// words per minute for `chars` typed over `duration`. The clock starts on the first key, so
+// a test that ends on its first key has no duration and no meaningful speed, which is 0.
+fn wpm(chars: usize, duration: Duration) -> f32 {
+    let wpm = (chars as f32 / 5.0) / (duration.as_secs_f32() / 60_f32);
+    if wpm.is_finite() {
+        wpm
+    } else {
+        0.0
+    }
+}
+
+#[derive(Debug)]
+pub struct TypingStats {
+    pub wpm: f32,
+    pub accuracy: f32,
+    pub awpm: f32,
+    // seconds spent typing
+    pub duration: f32,
+    pub failed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{history::HistoryRecord, stats::counts_for_pb};
+    #[test]
+    fn test_insert_when_first_of_phrase_is_correct() {
+        let mut t = Typing::new(PromptSettings::new()).unwrap();
+
+        t.input('t');
+
+        println!("{:?}", t);
+    }
+
+    #[test]
+    fn test_insert_when_first_of_phrase_is_incorrect() {
+        let mut t = Typing::new(PromptSettings::new()).unwrap();
+
+        t.input('t');
+        t.input('c');
+        t.input('e');
+
+        println!("{:?}", t);
+    }
+
+    #[test]
+    fn test_validate_replaces_out_of_range_settings() {
+        let mut settings = PromptSettings {
+            wc: 0,
+            time: 7,
+            language: "klingon".to_string(),
+            ..PromptSettings::new()
+        };
+        settings.validate();
+
+        assert_eq!(settings.wc, 25);
+        assert_eq!(settings.time, 30);
+        assert_eq!(settings.language, DEFAULT_LANGUAGE);
+    }
+
+    fn typing_with(phrase: &str, error_mode: ErrorMode) -> Typing {
+        let settings = PromptSettings {
+            error_mode,
+            ..PromptSettings::new()
+        };
+        Typing::with_phrase(phrase.replace(' ', "•").chars().collect(), settings)
+    }
+
+    #[test]
+    fn test_letter_mode_blocks_on_wrong_character() {
+        let mut t = typing_with("ab", ErrorMode::Letter);
+
+        assert!(!t.input('x'));
+        assert_eq!(t.position, 0);
+        assert!(!t.input('a'));
+        assert!(t.input('b'));
+        assert_eq!(t.calculate_statistics().accuracy, 2.0 / 3.0 * 100.0);
+    }
+
+    #[test]
+    fn test_word_mode_refuses_space_until_word_is_correct() {
+        let mut t = typing_with("ab cd", ErrorMode::Word);
+
+        t.input('a');
+        t.input('x');
+        assert_eq!(t.position, 2);
+        t.input('•');
+        assert_eq!(t.position, 2);
+
+        t.backspace();
+        t.input('b');
+        t.input('•');
+        assert_eq!(t.position, 3);
+
+        // cannot go back into the previous word
+        t.backspace();
+        assert_eq!(t.position, 3);
+    }
+
+    #[test]
+    fn test_word_mode_last_word_must_be_correct_to_finish() {
+        let mut t = typing_with("ab", ErrorMode::Word);
+
+        t.input('a');
+        assert!(!t.input('x'));
+        assert!(!t.input('b'));
+        t.backspace();
+        assert!(t.input('b'));
+    }
+
+    #[test]
+    fn test_free_mode_moves_on_after_errors() {
+        let mut t = typing_with("ab", ErrorMode::Free);
+
+        t.input('x');
+        assert!(t.input('y'));
+        assert!(!t.failed());
+        assert_eq!(t.calculate_statistics().accuracy, 0.0);
+    }
+
+    #[test]
+    fn test_sudden_death_fails_on_first_error() {
+        let mut t = typing_with("abc", ErrorMode::SuddenDeath);
+
+        t.input('a');
+        assert!(t.input('x'));
+        assert!(t.failed());
+    }
+
+    #[test]
+    fn test_min_accuracy_ends_test_early() {
+        let mut t = typing_with("aaaaaaaaaaaaaaaaaaaa", ErrorMode::Free);
+        t.settings.min_accuracy = 95;
+
+        for _ in 0..9 {
+            assert!(!t.input('a'));
+        }
+        assert!(t.input('x'));
+        assert!(t.failed());
+    }
+
+    #[test]
+    fn test_drill_words_contain_an_ngram() {
+        let language = LanguagePrompt::get(DEFAULT_LANGUAGE).unwrap();
+        let ngrams = vec!["th".to_string(), "ing".to_string()];
+
+        let prompt: String = language
+            .generate_drill(&ngrams, false, 50, 1)
+            .iter()
+            .collect();
+        let words: Vec<&str> = prompt.split('•').collect();
+        assert_eq!(words.len(), 50);
+        assert!(words
+            .iter()
+            .all(|w| w.to_lowercase().contains("th") || w.to_lowercase().contains("ing")));
+
+        let prompt: String = language
+            .generate_drill(&ngrams, true, 10, 1)
+            .iter()
+            .collect();
+        assert!(prompt
+            .split('•')
+            .all(|w| w.replace("th", "").is_empty() || w.replace("ing", "").is_empty()));
+    }
+
+    #[test]
+    fn test_lesson_prompt_only_uses_charset() {
+        let language = LanguagePrompt::get(DEFAULT_LANGUAGE).unwrap();
+
+        let prompt = language.generate_lesson("asdfghjkl", "asdfghjkl", 30, 1);
+        assert!(prompt.iter().all(|c| "asdfghjkl•".contains(*c)));
+    }
+
+    #[test]
+    fn test_time_paused_is_not_counted() {
+        let mut t = typing_with("ab", ErrorMode::Letter);
+        t.input('a');
+        t.pause();
+        std::thread::sleep(Duration::from_millis(50));
+        t.resume();
+        t.input('b');
+        assert!(t.keystrokes()[1].ms < 50);
+    }
+
+    #[test]
+    fn test_idle_pause_leaves_out_the_idle_time() {
+        let mut t = typing_with("ab", ErrorMode::Letter);
+        assert_eq!(t.idle_for(), None);
+        t.input('a');
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(t.idle_for().unwrap() >= Duration::from_millis(50));
+
+        t.pause_idle();
+        assert_eq!(t.idle_for(), None);
+        t.resume();
+        t.input('b');
+        assert!(t.keystrokes()[1].ms < 50);
+    }
+
+    #[test]
+    fn test_manual_clock_gives_exact_timing() {
+        let mut t = typing_with("ab•cd", ErrorMode::Letter);
+        t.set_clock(Clock::manual());
+        for c in "ab•cd".chars() {
+            t.advance_clock(Duration::from_millis(150));
+            t.input(c);
+        }
+        let ms: Vec<u64> = t.keystrokes().iter().map(|k| k.ms).collect();
+        assert_eq!(ms, vec![0, 150, 300, 450, 600]);
+        assert_eq!(t.elapsed(), Duration::from_millis(600));
+        let stats = t.calculate_statistics();
+        assert_eq!(stats.duration, 0.6);
+        assert!((stats.wpm - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_test_ending_on_its_first_key_has_no_speed() {
+        let mut t = typing_with("a", ErrorMode::Letter);
+        t.set_clock(Clock::manual());
+        t.advance_clock(Duration::from_millis(150));
+        assert!(t.input('a'));
+        let stats = t.calculate_statistics();
+        assert_eq!((stats.duration, stats.wpm, stats.awpm), (0.0, 0.0, 0.0));
+        assert_eq!(t.live_wpm(), 0.0);
+
+        let record = HistoryRecord::new(&t.settings, &stats, t.keystrokes().to_vec());
+        assert!(!counts_for_pb(&record));
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.contains("\"wpm\":0.0"), "{}", json);
+    }
+
+    #[test]
+    fn test_generators_give_an_empty_prompt_for_empty_input() {
+        let language = LanguagePrompt::for_settings(&PromptSettings::new()).unwrap();
+        assert!(LanguagePrompt::generate_practice(&[], 10, 1).is_empty());
+        let empty = LanguagePrompt::default();
+        assert!(empty.generate(&PromptSettings::new(), None, 1).is_empty());
+        assert!(language.generate_drill(&[], false, 10, 1).is_empty());
+        assert!(language.generate_drill(&[], true, 10, 1).is_empty());
+        assert!(language.generate_lesson("", "", 10, 1).is_empty());
+        let words = language.generate_lesson("asdf", "", 10, 1);
+        assert!(!words.is_empty() && words.iter().all(|c| "asdf•".contains(*c)));
+    }
+
+    #[test]
+    fn test_missed_words_are_kept_after_backspace() {
+        let mut t = typing_with("the•quick•fox", ErrorMode::Free);
+        for c in "thx".chars() {
+            t.input(c);
+        }
+        t.backspace();
+        for c in "e•quick•fix".chars() {
+            t.input(c);
+        }
+        assert_eq!(t.missed_words(), vec!["the", "fox"]);
+
+        t.reset();
+        assert!(t.missed_words().is_empty());
+    }
+
+    #[test]
+    fn test_state_model_exposes_words_and_caret() {
+        let mut t = typing_with("the•fox", ErrorMode::Letter);
+        assert_eq!(t.words(), vec![0..3, 4..7]);
+        t.input('t');
+        t.input('x');
+        assert_eq!(t.caret(), Some(1));
+        assert_eq!(t.letters()[0].state, LetterState::Correct);
+        assert_eq!(t.letters()[1].state, LetterState::Incorrect);
+        for c in "he•fox".chars() {
+            t.input(c);
+        }
+        assert_eq!(t.caret(), None);
+    }
+
+    #[test]
+    fn test_same_seed_generates_the_same_prompt() {
+        let language = LanguagePrompt {
+            words: ["the", "quick", "brown", "fox"].map(String::from).to_vec(),
+            ..LanguagePrompt::default()
+        };
+        let settings = PromptSettings {
+            wc: 6,
+            ..PromptSettings::new()
+        };
+
+        let prompt: String = language.generate(&settings, None, 42).iter().collect();
+        assert_eq!(prompt, "brown•brown•the•brown•the•fox");
+        assert_eq!(
+            language.generate(&settings, None, 42),
+            language.generate(&settings, None, 42)
+        );
+        assert_ne!(
+            language.generate(&settings, None, 42),
+            language.generate(&settings, None, 43)
+        );
+    }
+
+    #[test]
+    fn test_wc_cycles_through_presets() {
+        let mut settings = PromptSettings {
+            wc: 30,
+            wc_presets: vec![10, 40, 500],
+            ..PromptSettings::new()
+        };
+
+        settings.next_wc();
+        assert_eq!(settings.wc, 40);
+        settings.next_wc();
+        settings.next_wc();
+        assert_eq!(settings.wc, 10);
+        settings.previous_wc();
+        assert_eq!(settings.wc, 500);
+
+        assert!(settings.set_wc(10_001).is_err());
+        assert!(settings.set_wc_presets(vec![0, 5]).is_err());
+        assert_eq!(settings.set_wc(10_000), Ok(()));
+    }
+}
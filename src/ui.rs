@@ -1,14 +1,32 @@
+pub mod prompt;
+
+use std::time::Duration;
+
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
-    text::Line,
-    widgets::{Block, Clear, HighlightSpacing, List, ListItem, Padding, Paragraph, Wrap},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        Axis, Block, Chart, Clear, Dataset, GraphType, HighlightSpacing, List, ListItem, Padding,
+        Paragraph, Row, Table, Wrap,
+    },
     Frame,
 };
 use tui_big_text::BigText;
 
-use crate::app::{App, Pages};
+use crate::{
+    app::{
+        pages::{
+            drill::SUGGESTED_NGRAMS,
+            lessons::CURRICULUM,
+            stats::{moving_average, MOVING_AVERAGE_WINDOW},
+        },
+        App, Pages,
+    },
+    typing::Mode,
+};
 
 /// Renders the user interface widgets.
 pub fn render(app: &mut App, frame: &mut Frame) {
@@ -22,12 +40,22 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     match app.current_page {
         Pages::Menu => render_menu(frame, app, area, &mut a_buf),
         Pages::Typing => render_typing(frame, app, area),
-        Pages::Stats => todo!(),
+        Pages::Stats => render_history(frame, app, area),
         Pages::Pause => render_typing(frame, app, area),
+        Pages::Settings => render_settings_page(frame, app, area),
+        Pages::Heatmap => render_heatmap(frame, app, area),
+        Pages::Drill => render_drill(frame, app, area),
+        Pages::Lessons => render_lessons(frame, app, area),
+        Pages::Results => render_results(frame, app, area),
+        Pages::Records => render_records(frame, app, area),
+    }
+    if app.error.is_some() {
+        error_popup(frame, app, area);
     }
 }
 
 fn render_menu(frame: &mut Frame, app: &mut App, smart_area: Rect, _buf: &mut Buffer) {
+    let palette = app.prompt_settings.theme.palette();
     let [top_area, bottom_area] =
         { Layout::vertical([Constraint::Fill(30), Constraint::Fill(70)]).areas(smart_area) };
 
@@ -41,28 +69,35 @@ fn render_menu(frame: &mut Frame, app: &mut App, smart_area: Rect, _buf: &mut Bu
         .highlight_style(Style::default())
         .highlight_symbol(">")
         .highlight_spacing(HighlightSpacing::Always)
-        .block(Block::new().bg(Color::Black).padding(Padding::new(
+        .style(Style::new().fg(palette.fg))
+        .block(Block::new().bg(palette.bg).padding(Padding::new(
             bottom_area.width / 4,
             bottom_area.width / 4,
             0,
             0,
         )));
 
-    render_blank(frame, top_area);
+    render_blank(frame, app, top_area);
 
     frame.render_widget(
         BigText::builder()
             .pixel_size(tui_big_text::PixelSize::Full)
-            .style(Style::new().white().bg(Color::Black))
-            .lines(vec!["Type Fast!".white().into()])
+            .style(Style::new().fg(palette.fg).bg(palette.bg))
+            .lines(vec!["Type Fast!".fg(palette.fg).into()])
             .alignment(Alignment::Center)
             .build(),
         top_area,
     );
+    app.menu.area = bottom_area;
     frame.render_stateful_widget(list, bottom_area, &mut app.menu.current_selection);
 }
 
 fn render_typing(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let (palette, caret) = {
+        let settings = &app.prompt_settings;
+        (settings.theme.palette(), settings.caret)
+    };
+    app.prompt_text.update(&app.typing, palette, caret);
     let [top_area, prompt_area, bottom_area] = {
         Layout::vertical([
             Constraint::Fill(20),
@@ -72,36 +107,47 @@ fn render_typing(frame: &mut Frame, app: &mut App, smart_area: Rect) {
         .areas(smart_area)
     };
 
-    if !app.prompt_settings.borrow().zen {
+    if !app.prompt_settings.zen {
         render_settings(frame, app, top_area);
     } else {
-        render_blank(frame, top_area);
+        app.settings_bar = Default::default();
+        render_blank(frame, app, top_area);
     }
 
+    // time left and speed of the running test
+    let live = match app.typing.settings.mode {
+        _ if !app.typing.started() || app.prompt_settings.zen => String::new(),
+        Mode::Time => {
+            let limit = Duration::from_secs(app.typing.settings.time as u64);
+            let left = limit
+                .saturating_sub(app.typing.elapsed())
+                .as_secs_f32()
+                .ceil();
+            format!(" {}s  {:.0} wpm ", left, app.typing.live_wpm())
+        }
+        Mode::Words => format!(" {:.0} wpm ", app.typing.live_wpm()),
+    };
+
     // TODO wrap on word end instead of characters, this may not be possible for my current implementation
-    frame.render_widget(
-        Paragraph::new(app.typing.text.clone())
-            .wrap(Wrap { trim: true })
-            .block(
-                Block::bordered()
-                    .hidden()
-                    .padding(Padding::new(10, 10, top_area.height / 2, 0))
-                    .title_alignment(Alignment::Center),
-            )
-            .style(Style::default().fg(Color::Black).bg(Color::Black))
-            .centered(),
-        prompt_area,
-    );
+    let block = Block::bordered()
+        .hidden()
+        .padding(Padding::new(10, 10, top_area.height / 2, 0))
+        .title(Line::from(live).fg(palette.accent))
+        .title_alignment(Alignment::Center)
+        .style(Style::default().fg(palette.bg).bg(palette.bg));
+    let text_area = block.inner(prompt_area);
+    frame.render_widget(block, prompt_area);
+    frame.render_widget(&app.prompt_text, text_area);
 
     // display the previous prompts stats
-    if !app.prompt_settings.borrow().zen {
+    if !app.prompt_settings.zen {
         render_stats(frame, app, bottom_area);
     } else {
-        render_blank(frame, bottom_area);
+        render_blank(frame, app, bottom_area);
     }
 
     if app.paused {
-        pause_popup(frame, smart_area);
+        pause_popup(frame, app, smart_area);
     }
 }
 
@@ -115,13 +161,15 @@ fn render_settings(frame: &mut Frame, app: &mut App, smart_area: Rect) {
         ])
         .areas(smart_area)
     };
-    let settings = app.typing.settings.borrow();
+    app.settings_bar = [one, two, three, four];
+    let settings = &app.prompt_settings;
+    let palette = settings.theme.palette();
     frame.render_widget(
         Paragraph::new(vec![Line::from(format!("WC: {}", settings.wc))])
             .block(
                 Block::new()
                     .padding(Padding::new(0, 0, smart_area.height / 2, 0))
-                    .bg(Color::Black),
+                    .bg(palette.bg),
             )
             .alignment(Alignment::Right),
         one,
@@ -134,7 +182,7 @@ fn render_settings(frame: &mut Frame, app: &mut App, smart_area: Rect) {
         .block(
             Block::new()
                 .padding(Padding::new(0, 0, smart_area.height / 2, 0))
-                .bg(Color::Black),
+                .bg(palette.bg),
         )
         .alignment(Alignment::Center),
         two,
@@ -144,7 +192,7 @@ fn render_settings(frame: &mut Frame, app: &mut App, smart_area: Rect) {
             .block(
                 Block::new()
                     .padding(Padding::new(0, 0, smart_area.height / 2, 0))
-                    .bg(Color::Black),
+                    .bg(palette.bg),
             )
             .alignment(Alignment::Center),
         three,
@@ -154,7 +202,7 @@ fn render_settings(frame: &mut Frame, app: &mut App, smart_area: Rect) {
             .block(
                 Block::new()
                     .padding(Padding::new(0, 0, smart_area.height / 2, 0))
-                    .bg(Color::Black),
+                    .bg(palette.bg),
             )
             .alignment(Alignment::Left),
         four,
@@ -162,6 +210,7 @@ fn render_settings(frame: &mut Frame, app: &mut App, smart_area: Rect) {
 }
 // todo, can probably break this out more
 fn render_stats(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
     let [left, center, right] = {
         Layout::horizontal([
             Constraint::Fill(33),
@@ -177,7 +226,7 @@ fn render_stats(frame: &mut Frame, app: &mut App, smart_area: Rect) {
         .block(
             Block::new()
                 .padding(Padding::new(0, 0, smart_area.height / 2, 0))
-                .bg(Color::Black),
+                .bg(palette.bg),
         )
         .alignment(Alignment::Right),
         left,
@@ -189,38 +238,693 @@ fn render_stats(frame: &mut Frame, app: &mut App, smart_area: Rect) {
         .block(
             Block::new()
                 .padding(Padding::new(0, 0, smart_area.height / 2, 0))
-                .bg(Color::Black),
+                .bg(palette.bg),
         )
         .alignment(Alignment::Center),
         center,
     );
     frame.render_widget(
         Paragraph::new(vec![Line::from(
-            format!("AWPM: {:.2}", app.previous_stats.awpm).as_str(),
+            format!(
+                "AWPM: {:.2}{}",
+                app.previous_stats.awpm,
+                if app.previous_stats.failed {
+                    " (failed)"
+                } else {
+                    ""
+                }
+            )
+            .as_str(),
         )])
         .block(
             Block::new()
                 .padding(Padding::new(0, 0, smart_area.height / 2, 0))
-                .bg(Color::Black),
+                .bg(palette.bg),
         )
         .alignment(Alignment::Left),
         right,
     );
 }
 
-// Render black blocks for given area, useful for zen mode
-fn render_blank(frame: &mut Frame, area: Rect) {
-    frame.render_widget(Block::new().bg(Color::Black), area);
+fn render_settings_page(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
+    let [top_area, list_area, bottom_area] = {
+        Layout::vertical([
+            Constraint::Fill(20),
+            Constraint::Fill(60),
+            Constraint::Fill(20),
+        ])
+        .areas(smart_area)
+    };
+
+    frame.render_widget(
+        Paragraph::new("Settings")
+            .style(Style::new().fg(palette.accent).bg(palette.bg))
+            .block(Block::new().padding(Padding::new(0, 0, top_area.height / 2, 0)))
+            .alignment(Alignment::Center),
+        top_area,
+    );
+
+    let list_items: Vec<ListItem> = {
+        let settings = &app.prompt_settings;
+        let selected = app.settings.current_selection.selected();
+        app.settings
+            .options
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                // show the text being entered in place of the value
+                let value = match &app.settings.input {
+                    Some(input) if selected == Some(i) => format!("{}_", input),
+                    _ => x.value(settings),
+                };
+                ListItem::from(format!("{:<16}{}", x.to_string(), value))
+            })
+            .collect()
+    };
+    let list = List::new(list_items)
+        .highlight_style(Style::new().fg(palette.accent))
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always)
+        .style(Style::new().fg(palette.fg))
+        .block(Block::new().bg(palette.bg).padding(Padding::new(
+            list_area.width / 3,
+            list_area.width / 4,
+            0,
+            0,
+        )));
+    frame.render_stateful_widget(list, list_area, &mut app.settings.current_selection);
+
+    let help = match &app.settings.message {
+        Some(message) => Line::from(message.as_str()).fg(palette.incorrect),
+        None if app.settings.input.is_some() => {
+            Line::from("ENTER apply, ESC cancel").fg(palette.correct)
+        }
+        None => {
+            Line::from("h/l change value, ENTER edit, ESC save and return, q return without saving")
+                .fg(palette.correct)
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(help)
+            .block(Block::new().bg(palette.bg))
+            .alignment(Alignment::Center),
+        bottom_area,
+    );
+}
+
+fn render_lessons(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
+    let [top_area, list_area, bottom_area] = {
+        Layout::vertical([
+            Constraint::Fill(20),
+            Constraint::Fill(60),
+            Constraint::Fill(20),
+        ])
+        .areas(smart_area)
+    };
+    render_blank(frame, app, smart_area);
+
+    frame.render_widget(
+        Paragraph::new("Lessons")
+            .fg(palette.accent)
+            .block(Block::new().padding(Padding::new(0, 0, top_area.height / 2, 0)))
+            .alignment(Alignment::Center),
+        top_area,
+    );
+
+    let progress = &app.lessons.progress;
+    let list_items: Vec<ListItem> = CURRICULUM
+        .iter()
+        .enumerate()
+        .map(|(i, lesson)| {
+            let status = if progress.is_completed(i) {
+                "passed"
+            } else if progress.is_unlocked(i) {
+                "open"
+            } else {
+                "locked"
+            };
+            let item = ListItem::from(format!(
+                "{:<12}{:<14}{:>3} wpm {:>3}%  {}",
+                lesson.name, lesson.keys, lesson.min_wpm, lesson.min_accuracy, status
+            ));
+            if progress.is_unlocked(i) {
+                item.fg(palette.fg)
+            } else {
+                item.fg(palette.correct)
+            }
+        })
+        .collect();
+    let list = List::new(list_items)
+        .highlight_style(Style::new().fg(palette.accent))
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always)
+        .block(Block::new().padding(Padding::new(list_area.width / 4, 0, 0, 0)));
+    frame.render_stateful_widget(list, list_area, &mut app.lessons.current_selection);
+
+    let help = match &app.lessons.message {
+        Some(message) => Line::from(message.as_str()).fg(palette.accent),
+        None => Line::from("ENTER start lesson, ESC return").fg(palette.correct),
+    };
+    frame.render_widget(
+        Paragraph::new(help).alignment(Alignment::Center),
+        bottom_area,
+    );
+}
+
+fn render_drill(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
+    let [top_area, form_area, bottom_area] = {
+        Layout::vertical([
+            Constraint::Fill(20),
+            Constraint::Fill(60),
+            Constraint::Fill(20),
+        ])
+        .areas(smart_area)
+    };
+    render_blank(frame, app, smart_area);
+
+    frame.render_widget(
+        Paragraph::new("Drill")
+            .fg(palette.accent)
+            .block(Block::new().padding(Padding::new(0, 0, top_area.height / 2, 0)))
+            .alignment(Alignment::Center),
+        top_area,
+    );
+
+    let lines = vec![
+        Line::from(format!("N-grams: {}_", app.drill.input)).fg(palette.fg),
+        Line::from(format!(
+            "Words: {}",
+            if app.drill.synthetic {
+                "synthetic repetitions"
+            } else {
+                "real words"
+            }
+        ))
+        .fg(palette.fg),
+        Line::from(""),
+        Line::from(format!("Suggestions: {}", SUGGESTED_NGRAMS.join(", "))).fg(palette.correct),
+    ];
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::new().padding(Padding::new(form_area.width / 3, 0, 0, 0)))
+            .alignment(Alignment::Left),
+        form_area,
+    );
+
+    let help = match &app.drill.message {
+        Some(message) => Line::from(message.as_str()).fg(palette.incorrect),
+        None => Line::from("comma separated n-grams, TAB real/synthetic, ENTER start, ESC return")
+            .fg(palette.correct),
+    };
+    frame.render_widget(
+        Paragraph::new(help).alignment(Alignment::Center),
+        bottom_area,
+    );
 }
 
-fn pause_popup(frame: &mut Frame, area: Rect) {
+fn render_results(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
+    let [top_area, stats_area, list_area, bottom_area] = {
+        Layout::vertical([
+            Constraint::Fill(20),
+            Constraint::Fill(30),
+            Constraint::Fill(30),
+            Constraint::Fill(20),
+        ])
+        .areas(smart_area)
+    };
+    render_blank(frame, app, smart_area);
+
+    frame.render_widget(
+        Paragraph::new("Results")
+            .fg(palette.accent)
+            .block(Block::new().padding(Padding::new(0, 0, top_area.height / 2, 0)))
+            .alignment(Alignment::Center),
+        top_area,
+    );
+
+    let stats = &app.previous_stats;
+    let results = &app.results;
+    let pb = if results.flagged {
+        Line::from("keys were too fast to be typed by hand, this test does not count for PBs")
+            .fg(palette.incorrect)
+    } else if results.new_pb {
+        Line::from("new PB!").fg(palette.accent)
+    } else {
+        match results.previous_best {
+            Some(best) => Line::from(format!("PB: {:.2} wpm", best)).fg(palette.correct),
+            None => Line::from(""),
+        }
+    };
+    let lines = vec![
+        Line::from(format!(
+            "WPM: {:.2}   Accuracy: {:.2}   AWPM: {:.2}",
+            stats.wpm, stats.accuracy, stats.awpm
+        ))
+        .fg(palette.fg),
+        Line::from(format!(
+            "Time: {:.1}s{}",
+            stats.duration,
+            if stats.failed { " (failed)" } else { "" }
+        ))
+        .fg(palette.fg),
+        match app.prompt_seed {
+            Some(seed) => Line::from(format!("Seed: {}", seed)).fg(palette.correct),
+            None => Line::from("Seed: adaptive prompt").fg(palette.correct),
+        },
+        Line::from(""),
+        pb,
+    ];
+    frame.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center),
+        stats_area,
+    );
+
+    let list_items: Vec<ListItem> = results
+        .options
+        .iter()
+        .map(|x| ListItem::from(x.to_string()))
+        .collect();
+    let list = List::new(list_items)
+        .highlight_style(Style::new().fg(palette.accent))
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always)
+        .style(Style::new().fg(palette.fg))
+        .block(Block::new().padding(Padding::new(list_area.width * 2 / 5, 0, 0, 0)));
+    app.results.area = list_area;
+    frame.render_stateful_widget(list, list_area, &mut app.results.current_selection);
+
+    frame.render_widget(
+        Paragraph::new("ENTER select, ESC return to menu")
+            .fg(palette.correct)
+            .alignment(Alignment::Center),
+        bottom_area,
+    );
+}
+
+fn render_records(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
+    let [title_area, table_area, help_area] = {
+        Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(smart_area)
+    };
+    render_blank(frame, app, smart_area);
+
+    frame.render_widget(
+        Paragraph::new("Records")
+            .fg(palette.accent)
+            .block(Block::new().padding(Padding::new(0, 0, 1, 0)))
+            .alignment(Alignment::Center),
+        title_area,
+    );
+
+    if app.records.bests.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No personal bests yet, complete a test to set one")
+                .fg(palette.fg)
+                .alignment(Alignment::Center),
+            table_area,
+        );
+    } else {
+        let rows: Vec<Row> = app
+            .records
+            .bests
+            .iter()
+            .map(|(category, record)| {
+                Row::new(vec![
+                    category.to_string(),
+                    format!("{:.2}", record.wpm),
+                    format!("{:.2}%", record.accuracy),
+                    format!("{:.2}", record.awpm),
+                    record.timestamp.format("%Y-%m-%d").to_string(),
+                ])
+            })
+            .collect();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(8),
+                Constraint::Length(9),
+                Constraint::Length(8),
+                Constraint::Length(10),
+            ],
+        )
+        .header(Row::new(vec!["Category", "WPM", "Accuracy", "AWPM", "Date"]).fg(palette.accent))
+        .style(Style::new().fg(palette.fg))
+        .block(Block::new().padding(Padding::new(
+            table_area.width / 6,
+            table_area.width / 6,
+            1,
+            0,
+        )));
+        frame.render_widget(table, table_area);
+    }
+
+    frame.render_widget(
+        Paragraph::new("ESC return")
+            .fg(palette.correct)
+            .alignment(Alignment::Center),
+        help_area,
+    );
+}
+
+fn render_history(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
+    let [title_area, chart_area, summary_area, help_area] = {
+        Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+            Constraint::Length(1),
+        ])
+        .areas(smart_area)
+    };
+    render_blank(frame, app, smart_area);
+
+    let stats = &app.stats;
+    let filter = |value: Option<String>| value.unwrap_or_else(|| "all".to_string());
+    frame.render_widget(
+        Paragraph::new(format!(
+            "History - {} - mode: {}, language: {}, words: {}",
+            stats.range,
+            filter(stats.mode.map(|m| m.to_string())),
+            filter(stats.language.clone()),
+            filter(stats.wc.map(|wc| wc.to_string())),
+        ))
+        .fg(palette.accent)
+        .block(Block::new().padding(Padding::new(0, 0, 1, 0)))
+        .alignment(Alignment::Center),
+        title_area,
+    );
+
+    let records = stats.visible(&app.history);
+    let help = Paragraph::new("r range, m mode, l language, w word count, ESC return")
+        .fg(palette.correct)
+        .alignment(Alignment::Center);
+    if records.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No tests yet, complete a test to start your history")
+                .fg(palette.fg)
+                .alignment(Alignment::Center),
+            chart_area,
+        );
+        frame.render_widget(help, help_area);
+        return;
+    }
+
+    // x is measured in days since the first visible test
+    let first = records[0].timestamp;
+    let days =
+        |r: &crate::history::HistoryRecord| (r.timestamp - first).num_seconds() as f64 / 86_400.0;
+    let wpm: Vec<(f64, f64)> = records.iter().map(|r| (days(r), r.wpm as f64)).collect();
+    let accuracy: Vec<(f64, f64)> = records
+        .iter()
+        .map(|r| (days(r), r.accuracy as f64))
+        .collect();
+    let average: Vec<(f64, f64)> = moving_average(
+        &wpm.iter().map(|(_, y)| *y).collect::<Vec<_>>(),
+        MOVING_AVERAGE_WINDOW,
+    )
+    .into_iter()
+    .zip(&wpm)
+    .map(|(avg, (x, _))| (*x, avg))
+    .collect();
+
+    let max_x = wpm.last().map_or(1.0, |(x, _)| x.max(1.0 / 24.0));
+    let max_y = wpm.iter().map(|(_, y)| *y).fold(100.0, f64::max).ceil();
+    let last = records[records.len() - 1].timestamp;
+    let date = |t: chrono::DateTime<chrono::Utc>| t.format("%b %d").to_string();
+
+    let datasets = vec![
+        Dataset::default()
+            .name("wpm")
+            .marker(Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::new().fg(palette.fg))
+            .data(&wpm),
+        Dataset::default()
+            .name(format!("wpm avg of {}", MOVING_AVERAGE_WINDOW))
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(palette.accent))
+            .data(&average),
+        Dataset::default()
+            .name("accuracy %")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().fg(palette.incorrect))
+            .data(&accuracy),
+    ];
+    let chart = Chart::new(datasets)
+        .style(Style::new().fg(palette.fg))
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, max_x])
+                .labels([date(first), date(last)]),
+        )
+        .y_axis(Axis::default().bounds([0.0, max_y]).labels([
+            "0".to_string(),
+            format!("{:.0}", max_y / 2.0),
+            format!("{:.0}", max_y),
+        ]));
+    frame.render_widget(chart, chart_area);
+
+    let count = records.len() as f32;
+    let best = records.iter().map(|r| r.wpm).fold(0.0, f32::max);
+    frame.render_widget(
+        Paragraph::new(format!(
+            "{} tests, average {:.1} wpm at {:.1}%, best {:.1} wpm",
+            records.len(),
+            records.iter().map(|r| r.wpm).sum::<f32>() / count,
+            records.iter().map(|r| r.accuracy).sum::<f32>() / count,
+            best
+        ))
+        .fg(palette.fg)
+        .alignment(Alignment::Center),
+        summary_area,
+    );
+    frame.render_widget(help, help_area);
+}
+
+fn render_heatmap(frame: &mut Frame, app: &mut App, smart_area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
+    let heatmap = &app.heatmap;
+    let [title_area, keyboard_area, table_area, help_area] = {
+        Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(12),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(smart_area)
+    };
+    render_blank(frame, app, smart_area);
+
+    frame.render_widget(
+        Paragraph::new(format!(
+            "Key Stats - {} - colored by {}",
+            heatmap.layout, heatmap.metric
+        ))
+        .fg(palette.accent)
+        .block(Block::new().padding(Padding::new(0, 0, 1, 0)))
+        .alignment(Alignment::Center),
+        title_area,
+    );
+
+    // style for a key cap, colored from green (good) to red (bad)
+    let key_style = |key: char| match heatmap.heat(key) {
+        Some(heat) => {
+            let red = (255.0 * (heat * 2.0).min(1.0)) as u8;
+            let green = (255.0 * ((1.0 - heat) * 2.0).min(1.0)) as u8;
+            Style::new().fg(Color::Black).bg(Color::Rgb(red, green, 0))
+        }
+        None => Style::new().fg(palette.correct),
+    };
+
+    let mut lines: Vec<Line> = vec![];
+    for (indent, row) in heatmap.layout.rows().iter().enumerate() {
+        let mut spans = vec![Span::raw(" ".repeat(indent * 2))];
+        for key in row.chars() {
+            spans.push(Span::styled(format!(" {} ", key), key_style(key)));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        format!("{:^30}", "space"),
+        key_style(' '),
+    )));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .fg(palette.fg)
+            .alignment(Alignment::Center),
+        keyboard_area,
+    );
+
+    let rows: Vec<Row> = heatmap
+        .worst_keys(10)
+        .into_iter()
+        .map(|(key, stats)| {
+            let substitutions = stats
+                .top_substitutions(3)
+                .iter()
+                .map(|(c, n)| format!("{} ({})", display_key(*c), n))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Row::new(vec![
+                display_key(key),
+                format!("{:.1}%", stats.hit_rate() * 100.0),
+                stats
+                    .average_latency()
+                    .map_or("-".to_string(), |l| format!("{:.0}ms", l)),
+                stats
+                    .p95_latency()
+                    .map_or("-".to_string(), |l| format!("{}ms", l)),
+                substitutions,
+            ])
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(6),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(vec!["Key", "Hit rate", "Avg", "p95", "Often typed instead"]).fg(palette.accent),
+    )
+    .style(Style::new().fg(palette.fg))
+    .block(Block::new().padding(Padding::new(
+        table_area.width / 4,
+        table_area.width / 4,
+        1,
+        0,
+    )));
+    frame.render_widget(table, table_area);
+
+    frame.render_widget(
+        Paragraph::new("l change layout, m change metric, ESC return")
+            .fg(palette.correct)
+            .alignment(Alignment::Center),
+        help_area,
+    );
+}
+
+fn display_key(c: char) -> String {
+    match c {
+        ' ' => "space".to_string(),
+        c => c.to_string(),
+    }
+}
+
+// Render blank blocks for given area, useful for zen mode
+fn render_blank(frame: &mut Frame, app: &App, area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
+    frame.render_widget(Block::new().bg(palette.bg), area);
+}
+
+// Shows what went wrong over the current page, until any key is pressed
+fn error_popup(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(message) = &app.error else {
+        return;
+    };
+    let palette = app.prompt_settings.theme.palette();
+    let width = area.width.clamp(1, 60);
+    let [_, popup_area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Length(width),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    let [_, popup_area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(7.min(area.height)),
+        Constraint::Fill(1),
+    ])
+    .areas(popup_area);
+
+    frame.render_widget(Clear, popup_area);
+    let text = vec![
+        Line::from(message.as_str()).fg(palette.fg),
+        Line::default(),
+        Line::from("Press any key to close").fg(palette.correct),
+    ];
+    frame.render_widget(
+        Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .title(" Error ")
+                    .title_alignment(Alignment::Center)
+                    .fg(palette.incorrect)
+                    .bg(palette.bg),
+            ),
+        popup_area,
+    );
+}
+
+fn pause_popup(frame: &mut Frame, app: &mut App, area: Rect) {
+    let palette = app.prompt_settings.theme.palette();
     frame.render_widget(Clear, area); //this clears out the background
+    render_blank(frame, app, area);
+
+    let options = app.pause_popup.options.len() as u16;
+    let [_, title_area, _, list_area, _, help_area, _] = {
+        Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(options),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(area)
+    };
+    let title = match &app.pause_popup.message {
+        Some(message) => format!("PAUSED - {}", message),
+        None => "PAUSED".to_string(),
+    };
     frame.render_widget(
-        Paragraph::new("PAUSED").alignment(Alignment::Center).block(
-            Block::new()
-                .bg(Color::Black)
-                .padding(Padding::new(10, 10, area.height / 2, 0)),
-        ),
-        area,
+        Paragraph::new(title)
+            .fg(palette.accent)
+            .alignment(Alignment::Center),
+        title_area,
+    );
+
+    let list_items: Vec<ListItem> = app
+        .pause_popup
+        .options
+        .iter()
+        .map(|x| ListItem::from(x.to_string()))
+        .collect();
+    let list = List::new(list_items)
+        .highlight_style(Style::new().fg(palette.accent))
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always)
+        .style(Style::new().fg(palette.fg))
+        .block(Block::new().padding(Padding::new(list_area.width * 2 / 5, 0, 0, 0)));
+    app.pause_popup.area = list_area;
+    frame.render_stateful_widget(list, list_area, &mut app.pause_popup.current_selection);
+
+    frame.render_widget(
+        Paragraph::new("ENTER select, ESC resume")
+            .fg(palette.correct)
+            .alignment(Alignment::Center),
+        help_area,
     );
 }
@@ -0,0 +1,141 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::Widget,
+};
+
+use crate::{
+    theme::Palette,
+    typing::{Caret, LetterState, Typing, TypingLetter},
+};
+
+/// Styled letters of the typing prompt. The line is kept between draws and only the
+/// letters that changed since the last update are restyled, so long prompts stay responsive.
+#[derive(Debug, Default)]
+pub struct PromptText {
+    line: Line<'static>,
+    // revision of the prompt the line was built from, and how many of its changed
+    // letters have been restyled
+    revision: Option<u64>,
+    seen: usize,
+    caret: Option<usize>,
+    style: Option<(Palette, Caret)>,
+}
+
+impl PromptText {
+    /// Brings the line up to date with the state of `typing`.
+    pub fn update(&mut self, typing: &Typing, palette: Palette, caret: Caret) {
+        let letters = typing.letters();
+        let position = typing.caret();
+        let changed = typing.changed_letters();
+        // a new prompt, a reset or a new theme restyles everything
+        if self.style != Some((palette, caret))
+            || self.revision != Some(typing.revision())
+            || self.line.spans.len() != letters.len()
+        {
+            self.style = Some((palette, caret));
+            self.revision = Some(typing.revision());
+            self.seen = changed.len();
+            self.caret = position;
+            self.line = (0..letters.len())
+                .map(|i| self.span(i, letters[i]))
+                .collect();
+            return;
+        }
+
+        let old_caret = std::mem::replace(&mut self.caret, position);
+        let restyle = changed[self.seen..]
+            .iter()
+            .copied()
+            .chain(old_caret)
+            .chain(position);
+        for i in restyle {
+            self.line.spans[i] = self.span(i, letters[i]);
+        }
+        self.seen = changed.len();
+    }
+
+    /// The prompt as a single line.
+    pub fn line(&self) -> &Line<'static> {
+        &self.line
+    }
+
+    fn span(&self, i: usize, letter: TypingLetter) -> Span<'static> {
+        let Some((palette, caret)) = self.style else {
+            return Span::default();
+        };
+        let color = match letter.state {
+            LetterState::Unpressed => palette.fg,
+            LetterState::Incorrect => palette.incorrect,
+            LetterState::Correct => palette.correct,
+        };
+        let span = Span::styled(letter.value.to_string(), Style::new().fg(color));
+        if self.caret != Some(i) {
+            return span;
+        }
+        match caret {
+            Caret::Underline => span.underlined(),
+            Caret::Block => span.reversed(),
+            Caret::Off => span,
+        }
+    }
+}
+
+/// Draws the prompt wrapped to the width of the area, with each row centered. Every letter
+/// takes one cell, so the line is drawn as it is without copying it into a paragraph.
+/// Prompts taller than the area scroll to keep the caret's row and the next one in view.
+impl Widget for &PromptText {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let width = area.width as usize;
+        let caret_row = self.caret.unwrap_or(self.line.spans.len()) / width;
+        let lookahead = usize::from(area.height > 1);
+        let skip = (caret_row + 1 + lookahead).saturating_sub(area.height as usize);
+        let rows = self.line.spans.chunks(width).skip(skip);
+        for (y, row) in (area.top()..area.bottom()).zip(rows) {
+            // centered the way a paragraph centers its lines
+            let x = area.x + (area.width / 2).saturating_sub(row.len() as u16 / 2);
+            for (span, x) in row.iter().zip(x..) {
+                buf.set_span(x, y, span, 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{theme::Theme, typing::PromptSettings};
+
+    #[test]
+    fn test_update_restyles_changed_letters_like_a_full_build() {
+        let mut typing = Typing::with_phrase("ab•cd".chars().collect(), PromptSettings::new());
+        let palette = Theme::Dark.palette();
+
+        let mut text = PromptText::default();
+        text.update(&typing, palette, Caret::Block);
+        typing.input('a');
+        typing.input('x');
+        text.update(&typing, palette, Caret::Block);
+
+        let mut fresh = PromptText::default();
+        fresh.update(&typing, palette, Caret::Block);
+        assert_eq!(text.line(), fresh.line());
+        assert_eq!(text.line.spans[1].style.fg, Some(palette.incorrect));
+        assert_eq!(text.line.spans[0].style.fg, Some(palette.correct));
+
+        // keys typed between draws, moving back past the last drawn caret
+        typing.backspace();
+        typing.backspace();
+        typing.input('x');
+        typing.input('b');
+        text.update(&typing, palette, Caret::Block);
+        let mut fresh = PromptText::default();
+        fresh.update(&typing, palette, Caret::Block);
+        assert_eq!(text.line(), fresh.line());
+    }
+}
@@ -0,0 +1,180 @@
+//! Renders the app into a fixed size test terminal after scripted events and compares
+//! the screen against the snapshots in `tests/snapshots`. Review changed snapshots with
+//! `cargo insta review`, or accept them with `INSTA_UPDATE=always cargo test`.
+
+use std::{fmt::Write, time::Duration};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use insta::assert_snapshot;
+use ratatui::{backend::TestBackend, style::Modifier, Terminal};
+use type_fast::{
+    app::{App, Pages},
+    event::Event,
+    handler::handle_event,
+    theme::Theme,
+    typing::{Caret, Clock},
+    ui,
+};
+
+const SEED: u64 = 42;
+
+// time between scripted key presses, on a manual clock so timings are the same every run
+const KEY_DELAY: Duration = Duration::from_millis(200);
+
+fn app() -> App {
+    App::new().with_seed(SEED).with_clock(Clock::manual())
+}
+
+fn key(code: KeyCode) -> Event {
+    Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
+fn alt(c: char) -> Event {
+    Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT))
+}
+
+// key presses typing `text`, with `•` typed as a space
+fn type_text(text: &str) -> Vec<Event> {
+    text.chars()
+        .flat_map(|c| {
+            let c = if c == '•' { ' ' } else { c };
+            [Event::Wait(KEY_DELAY), key(KeyCode::Char(c))]
+        })
+        .collect()
+}
+
+fn run(app: &mut App, events: impl IntoIterator<Item = Event>) {
+    for event in events {
+        handle_event(event, app).unwrap();
+    }
+}
+
+fn prompt(app: &App) -> String {
+    app.typing.letters().iter().map(|l| l.value).collect()
+}
+
+fn render(app: &mut App, width: u16, height: u16) -> TestBackend {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| ui::render(app, frame)).unwrap();
+    terminal.backend().clone()
+}
+
+// every run of equally styled cells on each row, so snapshots catch changes to colors,
+// the caret and error highlighting that the text alone does not show
+fn styles(backend: &TestBackend) -> String {
+    let buffer = backend.buffer();
+    let area = buffer.area;
+    let mut out = String::new();
+    for y in area.top()..area.bottom() {
+        let mut x = area.left();
+        while x < area.right() {
+            let cell = &buffer[(x, y)];
+            let style = (cell.fg, cell.bg, cell.modifier);
+            let start = x;
+            while x < area.right() && {
+                let next = &buffer[(x, y)];
+                (next.fg, next.bg, next.modifier) == style
+            } {
+                x += 1;
+            }
+            let text: String = (start..x).map(|x| buffer[(x, y)].symbol()).collect();
+            let (fg, bg, modifier) = style;
+            writeln!(
+                out,
+                "{y:>2} {start:>3}..{x:<3} fg={fg} bg={bg} {modifier:?} {:?}",
+                text.trim_end()
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+#[test]
+fn menu() {
+    let mut app = app();
+    let backend = render(&mut app, 80, 24);
+    assert_snapshot!("menu_80x24", backend);
+    assert_snapshot!("menu_80x24_styles", styles(&backend));
+    assert_snapshot!("menu_120x40", render(&mut app, 120, 40));
+}
+
+#[test]
+fn typing() {
+    let mut app = app();
+    run(&mut app, [key(KeyCode::Enter)]);
+    let start: String = prompt(&app).chars().take(8).collect();
+    run(&mut app, type_text(&start));
+    assert_eq!(app.current_page, Pages::Typing);
+    let backend = render(&mut app, 100, 30);
+    assert_snapshot!(backend);
+    assert_snapshot!("typing_styles", styles(&backend));
+}
+
+#[test]
+fn typing_scrolls_to_the_caret() {
+    let mut app = app();
+    app.prompt_settings.wc = 1000;
+    run(&mut app, [key(KeyCode::Enter)]);
+    let start: String = prompt(&app).chars().take(3000).collect();
+    run(&mut app, type_text(&start));
+    let caret = app.typing.caret().unwrap();
+    let expected = app.typing.letters()[caret].value.to_string();
+
+    let backend = render(&mut app, 100, 30);
+    let buffer = backend.buffer();
+    let area = buffer.area;
+    let shown = (area.top()..area.bottom())
+        .flat_map(|y| (area.left()..area.right()).map(move |x| (x, y)))
+        .map(|position| &buffer[position])
+        .any(|cell| cell.modifier.contains(Modifier::UNDERLINED) && cell.symbol() == expected);
+    assert!(shown, "the caret is off screen");
+}
+
+#[test]
+fn typing_theme_caret_and_errors() {
+    let mut app = app();
+    app.prompt_settings.theme = Theme::Ocean;
+    app.prompt_settings.caret = Caret::Block;
+    run(&mut app, [key(KeyCode::Enter)]);
+    let start: String = prompt(&app).chars().take(4).collect();
+    run(&mut app, type_text(&start));
+    // a wrong key, shown in the error color
+    run(&mut app, type_text("#"));
+    assert_snapshot!(styles(&render(&mut app, 100, 30)));
+}
+
+#[test]
+fn pause() {
+    let mut app = app();
+    run(
+        &mut app,
+        [key(KeyCode::Enter), key(KeyCode::Esc), key(KeyCode::Down)],
+    );
+    assert_eq!(app.current_page, Pages::Pause);
+    let backend = render(&mut app, 100, 30);
+    assert_snapshot!(backend);
+    assert_snapshot!("pause_styles", styles(&backend));
+}
+
+#[test]
+fn results() {
+    let mut app = app();
+    run(&mut app, [key(KeyCode::Enter)]);
+    let text = prompt(&app);
+    run(&mut app, type_text(&text));
+    assert_eq!(app.current_page, Pages::Results);
+    let backend = render(&mut app, 100, 30);
+    assert_snapshot!(backend);
+    assert_snapshot!("results_styles", styles(&backend));
+}
+
+#[test]
+fn zen() {
+    let mut app = app();
+    run(&mut app, [key(KeyCode::Enter), alt('4')]);
+    assert!(app.prompt_settings.zen);
+    let backend = render(&mut app, 100, 30);
+    assert_snapshot!(backend);
+    assert_snapshot!("zen_styles", styles(&backend));
+}